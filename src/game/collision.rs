@@ -0,0 +1,197 @@
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::{CollisionEvent, CollisionGroups, Group};
+
+use super::{state::field_active, wrap::resolve_ghost, components::{Enemy, FromPlayer, Ghost, Laser, Meteor, Player}};
+
+// region:    --- Collision Layers
+/// What a collider is, deciding which other layers it can touch.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum CollisionLayer {
+    Player,
+    PlayerLaser,
+    Enemy,
+    EnemyLaser,
+    Meteor,
+    Pickup,
+}
+
+impl CollisionLayer {
+    pub fn group(&self) -> Group {
+        match self {
+            CollisionLayer::Player => Group::GROUP_1,
+            CollisionLayer::PlayerLaser => Group::GROUP_2,
+            CollisionLayer::Enemy => Group::GROUP_3,
+            CollisionLayer::EnemyLaser => Group::GROUP_4,
+            CollisionLayer::Meteor => Group::GROUP_5,
+            CollisionLayer::Pickup => Group::GROUP_6,
+        }
+    }
+
+    /// Layers this one interacts with. Rapier needs both sides to agree, so the table is symmetric.
+    pub fn filters(&self) -> Group {
+        match self {
+            CollisionLayer::Player => Self::groups(&[CollisionLayer::Enemy, CollisionLayer::EnemyLaser, CollisionLayer::Meteor, CollisionLayer::Pickup]),
+            CollisionLayer::PlayerLaser => Self::groups(&[CollisionLayer::Enemy, CollisionLayer::Meteor]),
+            CollisionLayer::Enemy => Self::groups(&[CollisionLayer::Player, CollisionLayer::PlayerLaser, CollisionLayer::Meteor]),
+            CollisionLayer::EnemyLaser => Self::groups(&[CollisionLayer::Player, CollisionLayer::Meteor]),
+            CollisionLayer::Meteor => Self::groups(&[CollisionLayer::Player, CollisionLayer::PlayerLaser, CollisionLayer::Enemy, CollisionLayer::EnemyLaser, CollisionLayer::Meteor]),
+            CollisionLayer::Pickup => Self::groups(&[CollisionLayer::Player]),
+        }
+    }
+
+    pub fn collision_groups(&self) -> CollisionGroups {
+        CollisionGroups::new(self.group(), self.filters())
+    }
+
+    fn groups(layers: &[CollisionLayer]) -> Group {
+        layers.iter().fold(Group::NONE, |groups, layer| groups | layer.group())
+    }
+}
+// endregion: --- Collision Layers
+
+// region:    --- Events
+#[derive(Event, Clone, Copy)]
+pub struct LaserHitMeteorEvent {
+    pub laser: Entity,
+    pub meteor: Entity,
+}
+
+/// A laser fired by the player hit a saucer.
+#[derive(Event, Clone, Copy)]
+pub struct LaserHitEnemyEvent {
+    pub laser: Entity,
+    pub enemy: Entity,
+}
+
+/// A laser fired by a saucer hit the ship.
+#[derive(Event, Clone, Copy)]
+pub struct LaserHitPlayerEvent {
+    pub laser: Entity,
+    pub player: Entity,
+}
+
+#[derive(Event, Clone, Copy)]
+pub struct MeteorHitPlayerEvent {
+    pub meteor: Entity,
+    pub player: Entity,
+}
+
+#[derive(Event, Clone, Copy)]
+pub struct MeteorHitEnemyEvent {
+    pub meteor: Entity,
+    pub enemy: Entity,
+}
+
+#[derive(Event, Clone, Copy)]
+pub struct EnemyHitPlayerEvent {
+    pub enemy: Entity,
+    pub player: Entity,
+}
+// endregion: --- Events
+
+pub struct CollisionPlugin;
+
+impl Plugin for CollisionPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_event::<LaserHitMeteorEvent>()
+            .add_event::<LaserHitEnemyEvent>()
+            .add_event::<LaserHitPlayerEvent>()
+            .add_event::<MeteorHitPlayerEvent>()
+            .add_event::<MeteorHitEnemyEvent>()
+            .add_event::<EnemyHitPlayerEvent>()
+            .add_systems(FixedUpdate, collision_dispatch_system.run_if(field_active));
+    }
+}
+
+/// Sorts the contacts Rapier reports into typed events, one per kind of interaction.
+/// Ghost colliders stand for their owner, a pair seen twice in a frame is sent once and a laser
+/// only ever hits the first thing it touches.
+pub fn collision_dispatch_system(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut laser_hit_meteor_events: EventWriter<LaserHitMeteorEvent>,
+    mut laser_hit_enemy_events: EventWriter<LaserHitEnemyEvent>,
+    mut laser_hit_player_events: EventWriter<LaserHitPlayerEvent>,
+    mut meteor_hit_player_events: EventWriter<MeteorHitPlayerEvent>,
+    mut meteor_hit_enemy_events: EventWriter<MeteorHitEnemyEvent>,
+    mut enemy_hit_player_events: EventWriter<EnemyHitPlayerEvent>,
+    query_layer: Query<(Has<Player>, Has<Enemy>, Has<Meteor>, Has<Laser>, Has<FromPlayer>)>,
+    query_ghost: Query<&Ghost>
+) {
+    let mut handled_pairs = HashSet::new();
+    let mut spent_lasers = HashSet::new();
+
+    for collision_event in collision_events.read() {
+        let CollisionEvent::Started(entity_a, entity_b, _) = collision_event else {
+            continue;
+        };
+
+        let (entity_a, entity_b) = (resolve_ghost(*entity_a, &query_ghost), resolve_ghost(*entity_b, &query_ghost));
+        if entity_a == entity_b || !handled_pairs.insert((entity_a.min(entity_b), entity_a.max(entity_b))) {
+            continue;
+        }
+
+        let (Some(layer_a), Some(layer_b)) = (get_layer(entity_a, &query_layer), get_layer(entity_b, &query_layer)) else {
+            continue;
+        };
+
+        // Put the pair in the order the match below expects.
+        let ((first, first_layer), (second, second_layer)) = if layer_a <= layer_b {
+            ((entity_a, layer_a), (entity_b, layer_b))
+        } else {
+            ((entity_b, layer_b), (entity_a, layer_a))
+        };
+
+        let laser = match (first_layer, second_layer) {
+            (CollisionLayer::PlayerLaser | CollisionLayer::EnemyLaser, _) => Some(first),
+            (_, CollisionLayer::PlayerLaser | CollisionLayer::EnemyLaser) => Some(second),
+            _ => None,
+        };
+        if laser.is_some_and(|laser| !spent_lasers.insert(laser)) {
+            continue;
+        }
+
+        match (first_layer, second_layer) {
+            (CollisionLayer::Player, CollisionLayer::Enemy) => {
+                enemy_hit_player_events.send(EnemyHitPlayerEvent { enemy: second, player: first });
+            },
+            (CollisionLayer::Player, CollisionLayer::EnemyLaser) => {
+                laser_hit_player_events.send(LaserHitPlayerEvent { laser: second, player: first });
+            },
+            (CollisionLayer::Player, CollisionLayer::Meteor) => {
+                meteor_hit_player_events.send(MeteorHitPlayerEvent { meteor: second, player: first });
+            },
+            (CollisionLayer::PlayerLaser, CollisionLayer::Enemy) => {
+                laser_hit_enemy_events.send(LaserHitEnemyEvent { laser: first, enemy: second });
+            },
+            (CollisionLayer::PlayerLaser | CollisionLayer::EnemyLaser, CollisionLayer::Meteor) => {
+                laser_hit_meteor_events.send(LaserHitMeteorEvent { laser: first, meteor: second });
+            },
+            (CollisionLayer::Enemy, CollisionLayer::Meteor) => {
+                meteor_hit_enemy_events.send(MeteorHitEnemyEvent { meteor: second, enemy: first });
+            },
+            _ => {
+                // Contacts nobody reacts to do not spend the laser.
+                if let Some(laser) = laser {
+                    spent_lasers.remove(&laser);
+                }
+            },
+        }
+    }
+}
+
+fn get_layer(
+    entity: Entity,
+    query_layer: &Query<(Has<Player>, Has<Enemy>, Has<Meteor>, Has<Laser>, Has<FromPlayer>)>
+) -> Option<CollisionLayer> {
+    match query_layer.get(entity).ok()? {
+        (true, ..) => Some(CollisionLayer::Player),
+        (_, true, ..) => Some(CollisionLayer::Enemy),
+        (_, _, true, ..) => Some(CollisionLayer::Meteor),
+        (.., true, true) => Some(CollisionLayer::PlayerLaser),
+        (.., true, false) => Some(CollisionLayer::EnemyLaser),
+        _ => None,
+    }
+}
@@ -1,56 +1,18 @@
 use std::f32::consts::PI;
 
-use bevy::{prelude::{Component, Vec2, Vec3}, reflect::Reflect, time::{Timer, TimerMode}};
+use bevy::{prelude::{Color, Component, Entity, IVec2, Transform, Vec2, Vec3}, reflect::Reflect, time::{Timer, TimerMode}};
 
-use crate::game::{BASE_SPEED, TIME_STEP};
+use crate::game::{meteor::MeteorKind, BASE_SPEED};
 
-use rand::{random, Rng};
+use rand::Rng;
 
 // region:    --- Common Components
-const MAX_ACCELERATION: f32 = 0.5;
+const HYPERSPACE_FADE_DURATION: f32 = 0.25;
 
-#[derive(Component)]
-pub struct Acceleration{
-    pub acceleration: f32,
-    pub x: f32,
-    pub y: f32,
-}
-
-impl Default for Acceleration {
-    fn default() -> Self {
-        Self { acceleration: 0., x: 0., y: 0. }
-    }
-}
-
-impl Acceleration {
-    pub fn accelerate(&mut self) {
-        self.acceleration += if self.acceleration < MAX_ACCELERATION { 0.001 } else { 0. };
-    }
-
-    pub fn stop(&mut self) {
-        self.acceleration = 0.;
-    }
-
-    pub fn calculate_translation(&mut self, rotation_angle_degrees: &f32) {
-        let angle_radians = rotation_angle_degrees.to_radians();
-        self.x += angle_radians.sin() * self.acceleration * -1.;
-        self.y += angle_radians.cos() * self.acceleration;
-
-        self.correct_max_acceleration();
-    }
-        
-    fn correct_max_acceleration(&mut self) {
-        if self.x > MAX_ACCELERATION {
-            self.x = MAX_ACCELERATION;
-        } else if self.x < -MAX_ACCELERATION {
-            self.x = -MAX_ACCELERATION;
-        }
-        if self.y > MAX_ACCELERATION {
-            self.y = MAX_ACCELERATION;
-        } else if self.y < -MAX_ACCELERATION {
-            self.y = -MAX_ACCELERATION;
-        }
-    }
+/// Set while the ship's engine pushes it along its heading.
+#[derive(Component, Default)]
+pub struct Thrust {
+    pub firing: bool,
 }
 
 const MAX_ANGLE_VALUES: (f32, f32) = (0., 360.);
@@ -67,8 +29,8 @@ impl Default for Direction {
 }
 
 impl Direction {
-    pub fn rotate(&mut self, rotation: f32) {
-        self.rotation_angle_degrees += rotation * TIME_STEP * BASE_SPEED;
+    pub fn rotate(&mut self, rotation: f32, delta_seconds: f32) {
+        self.rotation_angle_degrees += rotation * BASE_SPEED * delta_seconds;
         self.correct_angle();
     }
 
@@ -84,6 +46,27 @@ impl Direction {
 #[derive(Component)]
 pub struct Laser;
 
+/// Boundary behaviour: the entity reappears on the opposite edge, with ghost copies while it straddles one.
+#[derive(Component)]
+pub struct ScreenWrap;
+
+/// Boundary behaviour: the entity is despawned once it is more than `margin` outside the screen.
+#[derive(Component)]
+pub struct DespawnOffscreen {
+    pub margin: f32,
+}
+
+/// Boundary behaviour: the entity is held back at the screen edge.
+#[derive(Component)]
+pub struct ClampToScreen;
+
+/// Poses at the end of the last two simulation ticks, blended for rendering.
+#[derive(Component)]
+pub struct InterpolatedTransform {
+    pub previous: Transform,
+    pub current: Transform,
+}
+
 #[derive(Component)]
 pub struct LaserTimer(pub Timer);
 
@@ -100,22 +83,99 @@ pub struct Player;
 
 #[derive(Component)]
 pub struct FromPlayer;
+
+/// Ignores meteor hits on the ship until `timer` ends, blinking every `blink_timer` tick.
+#[derive(Component)]
+pub struct Invulnerability {
+    pub timer: Timer,
+    pub blink_timer: Timer,
+}
+
+pub enum HyperspacePhase {
+    FadeOut,
+    FadeIn,
+}
+
+/// Present while the ship is jumping through hyperspace; each phase lasts one run of `timer`.
+#[derive(Component)]
+pub struct Hyperspace {
+    pub phase: HyperspacePhase,
+    pub timer: Timer,
+}
+
+impl Default for Hyperspace {
+    fn default() -> Self {
+        Self {
+            phase: HyperspacePhase::FadeOut,
+            timer: Timer::from_seconds(HYPERSPACE_FADE_DURATION, TimerMode::Once),
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct HyperspaceCooldown(pub Timer);
+
+impl Default for HyperspaceCooldown {
+    fn default() -> Self {
+        Self(Timer::from_seconds(0., TimerMode::Once))
+    }
+}
 // endregion: --- Player Component
 
 // region:    --- Enemy Component
 #[derive(Component)]
 pub struct Enemy;
 
+/// Large saucers wander erratically, small ones fly a steady course.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Saucer {
+    Large,
+    Small,
+}
+
+/// Wavy course followed by a saucer: constant horizontal speed plus a sine on `y`.
+#[derive(Component)]
+pub struct EnemyPath {
+    pub direction: f32,
+    pub speed: f32,
+    pub amplitude: f32,
+    pub frequency: f32,
+    pub elapsed: f32,
+    pub course_timer: Timer,
+}
+
 #[derive(Component)]
 pub struct FromEnemy;
+
+/// Fires automatically each time `cooldown` elapses; `accuracy` goes from random (0) to leading the target (1).
+#[derive(Component)]
+pub struct Weapon {
+    pub cooldown: Timer,
+    pub accuracy: f32,
+    pub damage: f32,
+}
 // endregion: --- Enemy Component
 
+/// Copy of `owner` drawn on the opposite side of the screen while `owner` straddles an edge.
+/// `cell` is the screen-sized offset of the copy, e.g. `(-1, 0)` for the copy shown on the left.
+#[derive(Component)]
+pub struct Ghost {
+    pub owner: Entity,
+    pub cell: IVec2,
+}
+
 // region:    --- Explosion Component
 #[derive(Component)]
 pub struct Explosion;
 
+/// Requests an explosion for something destroyed, sized after it and carrying its velocity to the debris.
 #[derive(Component)]
-pub struct ExplosionToSpawn(pub Vec3);
+pub struct ExplosionToSpawn {
+    pub position: Vec3,
+    pub size: f32,
+    pub velocity: Vec2,
+    pub color: Color,
+}
 
 #[derive(Component)]
 pub struct ExplosionTimer(pub Timer);
@@ -125,6 +185,14 @@ impl Default for ExplosionTimer {
         Self(Timer::from_seconds(0.05, TimerMode::Repeating))
     }
 }
+
+#[derive(Component)]
+pub struct Debris {
+    pub velocity: Vec2,
+}
+
+#[derive(Component)]
+pub struct Flash;
 // endregion: --- Explosion Component
 
 #[derive(Component)]
@@ -137,8 +205,8 @@ pub struct RocketDrag;
 pub struct RocketDragTimer(pub Timer, pub Timer, pub Timer);
 
 impl RocketDragTimer {
-    pub fn new(mut factor: f32) -> Self {
-        factor += rand::thread_rng().gen::<f32>();
+    pub fn new(mut factor: f32, rng: &mut impl Rng) -> Self {
+        factor += rng.gen::<f32>();
         
         let duration_1_in_seconds = 1. * factor;
         let duration_2_in_seconds = duration_1_in_seconds + 0.5 * factor;
@@ -155,20 +223,62 @@ impl RocketDragTimer {
 #[derive(Component)]
 pub struct LifeTime(pub Timer);
 
+// region:    --- Health Component
+/// Hit points of anything that can be damaged: meteors, saucers and the ship.
+#[derive(Component)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Self { current: max, max }
+    }
+
+    /// Applies `damage` and returns whether this hit broke the entity.
+    pub fn take(&mut self, damage: &Damage) -> bool {
+        let was_alive = !self.is_depleted();
+        self.current = (self.current - damage.0).max(0.);
+
+        was_alive && self.is_depleted()
+    }
+
+    /// Set once the entity is broken, while it waits for its despawn.
+    pub fn is_depleted(&self) -> bool {
+        self.current <= 0.
+    }
+
+    pub fn fraction(&self) -> f32 {
+        self.current / self.max
+    }
+}
+
+/// Damage dealt to whatever this entity hits: lasers, and bodies ramming each other.
+#[derive(Component, Clone, Copy)]
+pub struct Damage(pub f32);
+// endregion: --- Health Component
+
 
 // region:    --- Meteor Component
 #[derive(Component)]
 pub struct Meteor;
 
+/// Weight authored in the wave file, before the kind's mass factor is applied to the collider.
+#[derive(Component)]
+pub struct Weight(pub f32);
+
+/// Meteor born from a split, moving with the momentum it inherited.
 #[derive(Component)]
-pub struct Weight(i32);
+pub struct Fragment;
 
 #[derive(Component)]
-pub struct MeteorType(u8);
+pub struct MeteorType(pub MeteorKind);
 
 #[derive(Component, Reflect)]
 pub struct MeteorLevel(pub u8);
 
+/// Child sprite drawing the cracks of a damaged meteor.
 #[derive(Component)]
-pub struct MeteorState(u8);
+pub struct CrackOverlay;
 // endregion: --- Meteor Component
\ No newline at end of file
@@ -0,0 +1,79 @@
+use bevy::{input::InputSystem, prelude::*};
+
+use super::state::field_active;
+
+// region:    --- Resources
+/// Controls applied to the ship during the current tick, read by the player systems instead of the keyboard.
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct ShipInput {
+    pub rotate_left: bool,
+    pub rotate_right: bool,
+    pub thrust: bool,
+    pub fire: bool,
+    pub hyperspace: bool,
+}
+
+impl ShipInput {
+    const ROTATE_LEFT: u8 = 1;
+    const ROTATE_RIGHT: u8 = 1 << 1;
+    const THRUST: u8 = 1 << 2;
+    const FIRE: u8 = 1 << 3;
+    const HYPERSPACE: u8 = 1 << 4;
+
+    pub fn to_bits(&self) -> u8 {
+        [
+            (self.rotate_left, Self::ROTATE_LEFT),
+            (self.rotate_right, Self::ROTATE_RIGHT),
+            (self.thrust, Self::THRUST),
+            (self.fire, Self::FIRE),
+            (self.hyperspace, Self::HYPERSPACE),
+        ].iter().filter(|(set, _)| *set).fold(0, |bits, (_, bit)| bits | bit)
+    }
+
+    pub fn from_bits(bits: u8) -> Self {
+        Self {
+            rotate_left: bits & Self::ROTATE_LEFT != 0,
+            rotate_right: bits & Self::ROTATE_RIGHT != 0,
+            thrust: bits & Self::THRUST != 0,
+            fire: bits & Self::FIRE != 0,
+            hyperspace: bits & Self::HYPERSPACE != 0,
+        }
+    }
+}
+
+/// Presses seen since the last tick, kept until a tick uses them: a frame may run no tick at all.
+#[derive(Resource, Default)]
+struct PendingPresses {
+    fire: bool,
+    hyperspace: bool,
+}
+// endregion: --- Resources
+
+/// Samples the keyboard once per tick into `ShipInput`.
+pub struct ControlsPlugin;
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<ShipInput>()
+            .init_resource::<PendingPresses>()
+            .add_systems(PreUpdate, latch_presses_system.after(InputSystem).run_if(field_active))
+            .add_systems(FixedPreUpdate, sample_ship_input_system.run_if(field_active));
+    }
+}
+
+fn latch_presses_system(kb: Res<ButtonInput<KeyCode>>, mut pending: ResMut<PendingPresses>) {
+    pending.fire |= kb.just_pressed(KeyCode::Space);
+    pending.hyperspace |= kb.just_pressed(KeyCode::ArrowDown);
+}
+
+pub fn sample_ship_input_system(kb: Res<ButtonInput<KeyCode>>, mut pending: ResMut<PendingPresses>, mut input: ResMut<ShipInput>) {
+    *input = ShipInput {
+        rotate_left: kb.pressed(KeyCode::ArrowLeft),
+        rotate_right: kb.pressed(KeyCode::ArrowRight),
+        thrust: kb.pressed(KeyCode::ArrowUp),
+        fire: pending.fire,
+        hyperspace: pending.hyperspace,
+    };
+    *pending = PendingPresses::default();
+}
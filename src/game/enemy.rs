@@ -0,0 +1,224 @@
+use std::f32::consts::PI;
+
+use bevy::{prelude::*, sprite::{MaterialMesh2dBundle, Mesh2dHandle}};
+use bevy_rapier2d::prelude::{ActiveCollisionTypes, ActiveEvents, Collider, RigidBody, Velocity};
+use rand::Rng;
+
+use super::{state::{field_active, GameState, InGame}, rng::{GameRng, RngStream}, collision::{collision_dispatch_system, CollisionLayer, EnemyHitPlayerEvent, LaserHitEnemyEvent, MeteorHitEnemyEvent}, meteor::{MeteorBlastEvent, BLAST_DAMAGE, BLAST_RADIUS}, score::{saucer_points, Score}, components::{Damage, DespawnOffscreen, Enemy, EnemyPath, ExplosionToSpawn, Health, Saucer, Weapon}, wave::{Wave, WaveSpawnSet}, weapon::Difficulty, WinSize, COLLISION_DAMAGE, ENEMY_MAX};
+
+// region:    --- Constants
+
+const ENEMY_SPAWN_INTERVAL: f32 = 6.;
+const ENEMY_MARGIN: f32 = 60.;
+const SMALL_SAUCER_CHANCE: f32 = 0.3;
+
+const LARGE_SAUCER_SIZE: (f32, f32) = (60., 24.);
+const SMALL_SAUCER_SIZE: (f32, f32) = (30., 12.);
+
+const LARGE_SAUCER_COLOR: Color = Color::srgb(0.8, 0.8, 0.9);
+const SMALL_SAUCER_COLOR: Color = Color::srgb(1., 0.4, 0.4);
+
+const LARGE_SAUCER_SPEED: f32 = 120.;
+const SMALL_SAUCER_SPEED: f32 = 180.;
+const LARGE_SAUCER_COURSE_CHANGE: f32 = 1.;
+
+const LARGE_SAUCER_FIRE_RATE: f32 = 1.5;
+const SMALL_SAUCER_FIRE_RATE: f32 = 1.;
+const LARGE_SAUCER_ACCURACY_FACTOR: f32 = 0.25;
+
+const LARGE_SAUCER_HEALTH: f32 = 2.;
+const SMALL_SAUCER_HEALTH: f32 = 1.;
+const SAUCER_LASER_DAMAGE: f32 = 1.;
+// endregion: --- Constants
+
+// region:    --- Resources
+#[derive(Resource)]
+pub struct EnemySpawnTimer(pub Timer);
+
+impl Default for EnemySpawnTimer {
+    fn default() -> Self {
+        Self(Timer::from_seconds(ENEMY_SPAWN_INTERVAL, TimerMode::Repeating))
+    }
+}
+// endregion: --- Resources
+
+pub struct EnemyPlugin;
+
+impl Plugin for EnemyPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<EnemySpawnTimer>()
+            .add_systems(OnEnter(InGame), reset_enemy_spawn_timer_system)
+            .add_systems(OnExit(GameState::WaveIntermission), reset_enemy_spawn_timer_system)
+            // Both draw from the saucer stream, in this order.
+            .add_systems(FixedUpdate, enemy_spawn_system.before(enemy_movement_system).run_if(in_state(GameState::Playing).and_then(enough_enemies_to_spawn)).in_set(WaveSpawnSet))
+            .add_systems(FixedUpdate, (
+                enemy_movement_system,
+                (enemy_collision_system, enemy_blast_system).chain().after(collision_dispatch_system),
+            ).run_if(field_active));
+    }
+}
+
+fn reset_enemy_spawn_timer_system(mut spawn_timer: ResMut<EnemySpawnTimer>) {
+    spawn_timer.0.reset();
+}
+
+fn enough_enemies_to_spawn(wave_resource: Option<Res<Wave>>) -> bool {
+    wave_resource.map_or(false, |wave| wave.has_enemies())
+}
+
+fn enemy_spawn_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    win_size: Res<WinSize>,
+    difficulty: Res<Difficulty>,
+    mut spawn_timer: ResMut<EnemySpawnTimer>,
+    mut wave_resource: ResMut<Wave>,
+    mut game_rng: ResMut<GameRng>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    query_enemy: Query<(), With<Enemy>>
+) {
+    if !spawn_timer.0.tick(time.delta()).just_finished() || query_enemy.iter().count() >= ENEMY_MAX as usize {
+        return;
+    }
+
+    *wave_resource.get_enemies() -= 1;
+
+    let rng = game_rng.stream(RngStream::Saucers);
+    let saucer = if rng.gen::<f32>() < SMALL_SAUCER_CHANCE { Saucer::Small } else { Saucer::Large };
+    let (size, color, speed, fire_rate, accuracy, health) = match saucer {
+        Saucer::Large => (LARGE_SAUCER_SIZE, LARGE_SAUCER_COLOR, LARGE_SAUCER_SPEED, LARGE_SAUCER_FIRE_RATE, difficulty.enemy_accuracy * LARGE_SAUCER_ACCURACY_FACTOR, LARGE_SAUCER_HEALTH),
+        Saucer::Small => (SMALL_SAUCER_SIZE, SMALL_SAUCER_COLOR, SMALL_SAUCER_SPEED, SMALL_SAUCER_FIRE_RATE, difficulty.enemy_accuracy, SMALL_SAUCER_HEALTH),
+    };
+
+    // Saucers enter from one side and cross the whole screen before leaving on the other.
+    let direction = if rng.gen_bool(0.5) { 1. } else { -1. };
+    let init_position = Vec3 {
+        x: -direction * (win_size.width / 2. + ENEMY_MARGIN / 2.),
+        y: win_size.height / 2. * rng.gen_range(-0.8..=0.8),
+        z: 10.
+    };
+
+    commands
+        .spawn(MaterialMesh2dBundle {
+            mesh: Mesh2dHandle(meshes.add(Ellipse::new(size.0 / 2., size.1 / 2.))),
+            material: materials.add(ColorMaterial::from(color)),
+            transform: Transform::from_translation(init_position),
+            ..default()
+        })
+        .insert(Enemy)
+        .insert(saucer)
+        .insert(DespawnOffscreen { margin: ENEMY_MARGIN })
+        .insert(StateScoped(InGame))
+        .insert(EnemyPath {
+            direction,
+            speed,
+            amplitude: size.1 * 2.,
+            frequency: PI / 2.,
+            elapsed: 0.,
+            course_timer: Timer::from_seconds(LARGE_SAUCER_COURSE_CHANGE, TimerMode::Repeating),
+        })
+        .insert(Weapon {
+            cooldown: Timer::from_seconds(fire_rate, TimerMode::Repeating),
+            accuracy,
+            damage: SAUCER_LASER_DAMAGE,
+        })
+        .insert(Health::new(health))
+        .insert(Damage(COLLISION_DAMAGE))
+        .insert(RigidBody::KinematicVelocityBased)
+        .insert(Collider::cuboid(size.0 / 2., size.1 / 2.))
+        .insert(CollisionLayer::Enemy.collision_groups())
+        .insert(ActiveEvents::COLLISION_EVENTS)
+        .insert(ActiveCollisionTypes::default() | ActiveCollisionTypes::KINEMATIC_KINEMATIC | ActiveCollisionTypes::KINEMATIC_STATIC)
+        .insert(Velocity::zero());
+}
+
+fn enemy_movement_system(time: Res<Time>, mut game_rng: ResMut<GameRng>, mut query: Query<(&mut Velocity, &mut EnemyPath, &Saucer)>) {
+    let rng = game_rng.stream(RngStream::Saucers);
+
+    for (mut velocity, mut path, saucer) in query.iter_mut() {
+        path.elapsed += time.delta_seconds();
+
+        if *saucer == Saucer::Large && path.course_timer.tick(time.delta()).just_finished() {
+            path.amplitude = rng.gen_range(20.0..=120.);
+            path.frequency = rng.gen_range(PI / 4.0..=PI);
+        }
+
+        let vertical_speed = path.amplitude * path.frequency * (path.frequency * path.elapsed).cos();
+        velocity.linvel = Vec2::new(path.direction * path.speed, vertical_speed);
+    }
+}
+
+fn enemy_collision_system(
+    mut commands: Commands,
+    mut score: ResMut<Score>,
+    mut laser_hit_enemy_events: EventReader<LaserHitEnemyEvent>,
+    mut meteor_hit_enemy_events: EventReader<MeteorHitEnemyEvent>,
+    mut enemy_hit_player_events: EventReader<EnemyHitPlayerEvent>,
+    mut query_enemy: Query<(&Transform, &Velocity, &Saucer, &mut Health), With<Enemy>>,
+    query_damage: Query<&Damage>
+) {
+    // Only the player's lasers reach saucers, and they are worth points.
+    let laser_hits = laser_hit_enemy_events.read().map(|hit| (hit.enemy, hit.laser, true));
+    let meteor_hits = meteor_hit_enemy_events.read().map(|hit| (hit.enemy, hit.meteor, false));
+    let player_hits = enemy_hit_player_events.read().map(|hit| (hit.enemy, hit.player, false));
+
+    for (entity_enemy, other, shot_by_player) in laser_hits.chain(meteor_hits).chain(player_hits) {
+        let Ok(damage) = query_damage.get(other) else {
+            continue;
+        };
+        if shot_by_player {
+            commands.entity(other).despawn();
+        }
+
+        let Ok((transform, velocity, saucer, mut health)) = query_enemy.get_mut(entity_enemy) else {
+            continue;
+        };
+        if !health.take(damage) {
+            continue;
+        }
+
+        spawn_saucer_explosion(&mut commands, saucer, transform, velocity);
+        if shot_by_player {
+            score.add(saucer_points(saucer));
+        }
+        commands.entity(entity_enemy).despawn();
+    }
+}
+
+fn enemy_blast_system(
+    mut commands: Commands,
+    mut score: ResMut<Score>,
+    mut blast_events: EventReader<MeteorBlastEvent>,
+    mut query_enemy: Query<(Entity, &Transform, &Velocity, &Saucer, &mut Health), With<Enemy>>
+) {
+    for blast in blast_events.read() {
+        for (entity_enemy, transform, velocity, saucer, mut health) in &mut query_enemy {
+            if blast.position.truncate().distance(transform.translation.truncate()) > BLAST_RADIUS || !health.take(&BLAST_DAMAGE) {
+                continue;
+            }
+
+            spawn_saucer_explosion(&mut commands, saucer, transform, velocity);
+            if blast.by_player {
+                score.add(saucer_points(saucer));
+            }
+            commands.entity(entity_enemy).despawn();
+        }
+    }
+}
+
+fn spawn_saucer_explosion(commands: &mut Commands, saucer: &Saucer, transform: &Transform, velocity: &Velocity) {
+    let (size, color) = match saucer {
+        Saucer::Large => (LARGE_SAUCER_SIZE, LARGE_SAUCER_COLOR),
+        Saucer::Small => (SMALL_SAUCER_SIZE, SMALL_SAUCER_COLOR),
+    };
+
+    commands.spawn(ExplosionToSpawn {
+        position: transform.translation,
+        size: size.0,
+        velocity: velocity.linvel,
+        color,
+    })
+    .insert(StateScoped(InGame));
+}
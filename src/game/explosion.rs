@@ -0,0 +1,137 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use super::{state::{field_active, InGame}, rng::{GameRng, RngStream}, components::{Debris, Explosion, ExplosionTimer, ExplosionToSpawn, Flash, LifeTime}, GameTextures, EXPLOSION_FRAME_SIZE, EXPLOSION_LEN};
+
+const EXPLOSION_Z: f32 = 20.;
+
+/// One piece of debris per `DEBRIS_SPACING` pixels of the destroyed entity.
+const DEBRIS_SPACING: f32 = 8.;
+const DEBRIS_MAX: usize = 24;
+const DEBRIS_SIZE: f32 = 4.;
+const DEBRIS_SPEED: (f32, f32) = (40., 160.);
+const DEBRIS_LIFE_TIME: f32 = 0.8;
+
+const FLASH_DURATION: f32 = 0.12;
+/// Size of the flash relative to the destroyed entity.
+const FLASH_SCALE: f32 = 2.5;
+
+pub struct ExplosionPlugin;
+
+impl Plugin for ExplosionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (
+            explosion_to_spawn_system,
+            explosion_animation_system,
+            debris_system,
+            flash_system,
+        ).run_if(field_active));
+    }
+}
+
+/// Turns every `ExplosionToSpawn` request into an animated explosion, a burst of debris and a flash.
+fn explosion_to_spawn_system(
+    mut commands: Commands,
+    game_textures: Res<GameTextures>,
+    mut game_rng: ResMut<GameRng>,
+    query: Query<(Entity, &ExplosionToSpawn)>
+) {
+    let rng = game_rng.stream(RngStream::Cosmetic);
+
+    for (entity, explosion_to_spawn) in query.iter() {
+        commands.entity(entity).despawn();
+
+        let position = explosion_to_spawn.position.truncate();
+
+        commands
+            .spawn(SpriteBundle {
+                texture: game_textures.explosion.clone(),
+                transform: Transform {
+                    translation: position.extend(EXPLOSION_Z),
+                    scale: Vec3::splat(explosion_to_spawn.size / EXPLOSION_FRAME_SIZE),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .insert(TextureAtlas {
+                layout: game_textures.explosion_layout.clone(),
+                index: 0,
+            })
+            .insert(Explosion)
+            .insert(ExplosionTimer::default())
+            .insert(StateScoped(InGame));
+
+        commands
+            .spawn(SpriteBundle {
+                texture: game_textures.flash.clone(),
+                transform: Transform {
+                    translation: position.extend(EXPLOSION_Z + 1.),
+                    scale: Vec3::splat(explosion_to_spawn.size * FLASH_SCALE / EXPLOSION_FRAME_SIZE),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .insert(Flash)
+            .insert(LifeTime(Timer::from_seconds(FLASH_DURATION, TimerMode::Once)))
+            .insert(StateScoped(InGame));
+
+        let debris_count = ((explosion_to_spawn.size / DEBRIS_SPACING) as usize).clamp(1, DEBRIS_MAX);
+
+        for _ in 0..debris_count {
+            let direction = Vec2::from_angle(rng.gen_range(0.0..TAU));
+            let offset = direction * rng.gen_range(0.0..explosion_to_spawn.size / 4.);
+
+            commands
+                .spawn(SpriteBundle {
+                    sprite: Sprite {
+                        color: explosion_to_spawn.color,
+                        custom_size: Some(Vec2::splat(DEBRIS_SIZE * rng.gen_range(0.5..=1.5))),
+                        ..Default::default()
+                    },
+                    transform: Transform {
+                        translation: (position + offset).extend(EXPLOSION_Z - 1.),
+                        rotation: Quat::from_rotation_z(rng.gen_range(0.0..TAU)),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .insert(Debris {
+                    velocity: explosion_to_spawn.velocity + direction * rng.gen_range(DEBRIS_SPEED.0..=DEBRIS_SPEED.1),
+                })
+                .insert(LifeTime(Timer::from_seconds(DEBRIS_LIFE_TIME * rng.gen_range(0.6..=1.), TimerMode::Once)))
+                .insert(StateScoped(InGame));
+        }
+    }
+}
+
+fn explosion_animation_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut ExplosionTimer, &mut TextureAtlas), With<Explosion>>
+) {
+    for (entity, mut timer, mut texture_atlas) in query.iter_mut() {
+        timer.0.tick(time.delta());
+
+        let index = texture_atlas.index + timer.0.times_finished_this_tick() as usize;
+        if index < EXPLOSION_LEN {
+            texture_atlas.index = index;
+        } else {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn debris_system(time: Res<Time>, mut query: Query<(&Debris, &LifeTime, &mut Transform, &mut Sprite)>) {
+    for (debris, life_time, mut transform, mut sprite) in query.iter_mut() {
+        transform.translation += (debris.velocity * time.delta_seconds()).extend(0.);
+        sprite.color.set_alpha(1. - life_time.0.fraction());
+    }
+}
+
+fn flash_system(mut query: Query<(&LifeTime, &mut Sprite), With<Flash>>) {
+    for (life_time, mut sprite) in query.iter_mut() {
+        sprite.color.set_alpha(1. - life_time.0.fraction());
+    }
+}
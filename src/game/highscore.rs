@@ -0,0 +1,305 @@
+use std::{path::PathBuf, time::{SystemTime, UNIX_EPOCH}};
+
+use bevy::prelude::*;
+use yaml_rust2::{yaml::Hash, Yaml, YamlEmitter, YamlLoader};
+
+use super::{replay::ReplayPlayback, score::Score, state::GameState, wave::Wave, GameMode};
+
+// region:    --- Constants
+
+const HIGH_SCORES_DIRECTORY: &str = "rust-asteroids";
+const HIGH_SCORES_FILE: &str = "highscores.yml";
+const HIGH_SCORES_MAX: usize = 10;
+const INITIALS_LENGTH: usize = 3;
+
+const TITLE_FONT_SIZE: f32 = 40.;
+const TABLE_FONT_SIZE: f32 = 24.;
+const PROMPT_FONT_SIZE: f32 = 20.;
+// endregion: --- Constants
+
+// region:    --- Resources
+#[derive(Debug, Clone)]
+pub struct HighScoreEntry {
+    pub name: String,
+    pub score: u32,
+    pub wave: usize,
+    pub date: String,
+    pub mode: GameMode,
+}
+
+#[derive(Resource, Default)]
+pub struct HighScores(pub Vec<HighScoreEntry>);
+
+impl HighScores {
+    pub fn qualifies(&self, score: u32) -> bool {
+        score > 0 && (self.0.len() < HIGH_SCORES_MAX || self.0.iter().any(|entry| score > entry.score))
+    }
+
+    pub fn insert(&mut self, entry: HighScoreEntry) {
+        let index = self.0.iter().position(|existing| entry.score > existing.score).unwrap_or(self.0.len());
+        self.0.insert(index, entry);
+        self.0.truncate(HIGH_SCORES_MAX);
+    }
+}
+
+/// Arcade-style initials being typed after a qualifying game over.
+#[derive(Resource)]
+pub struct InitialsEntry {
+    letters: [u8; INITIALS_LENGTH],
+    cursor: usize,
+    score: u32,
+    wave: usize,
+    mode: GameMode,
+}
+// endregion: --- Resources
+
+// region:    --- Components
+#[derive(Component)]
+struct HighScoreScreen;
+
+#[derive(Component)]
+struct InitialsText;
+// endregion: --- Components
+
+pub struct HighScorePlugin;
+
+impl Plugin for HighScorePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .insert_resource(load_high_scores())
+            .add_systems(OnEnter(GameState::GameOver), high_score_game_over_system)
+            .add_systems(OnExit(GameState::GameOver), clear_initials_entry_system)
+            .add_systems(Update, (
+                game_over_exit_system.run_if(not(resource_exists::<InitialsEntry>)),
+                initials_input_system.run_if(resource_exists::<InitialsEntry>),
+                update_initials_text_system.run_if(resource_exists_and_changed::<InitialsEntry>),
+            ).chain().run_if(in_state(GameState::GameOver)));
+    }
+}
+
+fn high_score_game_over_system(
+    mut commands: Commands,
+    score: Res<Score>,
+    game_mode: Res<GameMode>,
+    high_scores: Res<HighScores>,
+    wave: Option<Res<Wave>>,
+    playback: Option<Res<ReplayPlayback>>
+) {
+    let wave = wave.map_or(0, |wave| wave.get_number());
+
+    // A replayed game was already scored when it was played.
+    if playback.is_none() && high_scores.qualifies(score.points) {
+        commands.insert_resource(InitialsEntry {
+            letters: [b'A'; INITIALS_LENGTH],
+            cursor: 0,
+            score: score.points,
+            wave,
+            mode: *game_mode,
+        });
+        spawn_initials_screen(&mut commands);
+    } else {
+        spawn_high_score_table(&mut commands, &high_scores);
+    }
+}
+
+fn clear_initials_entry_system(mut commands: Commands) {
+    commands.remove_resource::<InitialsEntry>();
+}
+
+fn game_over_exit_system(kb: Res<ButtonInput<KeyCode>>, mut next_state: ResMut<NextState<GameState>>) {
+    if kb.just_pressed(KeyCode::Enter) {
+        next_state.set(GameState::MainMenu);
+    }
+}
+
+fn initials_input_system(
+    mut commands: Commands,
+    kb: Res<ButtonInput<KeyCode>>,
+    mut initials_entry: ResMut<InitialsEntry>,
+    mut high_scores: ResMut<HighScores>,
+    query_screen: Query<Entity, With<HighScoreScreen>>
+) {
+    let cursor = initials_entry.cursor;
+
+    if kb.just_pressed(KeyCode::ArrowUp) {
+        initials_entry.letters[cursor] = if initials_entry.letters[cursor] == b'Z' { b'A' } else { initials_entry.letters[cursor] + 1 };
+    } else if kb.just_pressed(KeyCode::ArrowDown) {
+        initials_entry.letters[cursor] = if initials_entry.letters[cursor] == b'A' { b'Z' } else { initials_entry.letters[cursor] - 1 };
+    } else if kb.just_pressed(KeyCode::ArrowLeft) {
+        initials_entry.cursor = cursor.saturating_sub(1);
+    } else if kb.just_pressed(KeyCode::ArrowRight) {
+        initials_entry.cursor = (cursor + 1).min(INITIALS_LENGTH - 1);
+    } else if kb.just_pressed(KeyCode::Enter) {
+        high_scores.insert(HighScoreEntry {
+            name: String::from_utf8_lossy(&initials_entry.letters).into_owned(),
+            score: initials_entry.score,
+            wave: initials_entry.wave,
+            date: today(),
+            mode: initials_entry.mode,
+        });
+        save_high_scores(&high_scores);
+
+        for entity in query_screen.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        commands.remove_resource::<InitialsEntry>();
+        spawn_high_score_table(&mut commands, &high_scores);
+    }
+}
+
+fn update_initials_text_system(initials_entry: Res<InitialsEntry>, mut query: Query<&mut Text, With<InitialsText>>) {
+    let initials = initials_entry.letters.iter()
+        .enumerate()
+        .map(|(index, letter)| if index == initials_entry.cursor { format!("[{}]", *letter as char) } else { format!(" {} ", *letter as char) })
+        .collect::<String>();
+
+    for mut text in query.iter_mut() {
+        text.sections[0].value = initials.clone();
+    }
+}
+
+fn spawn_screen(commands: &mut Commands, title: &str, spawn_content: impl FnOnce(&mut ChildBuilder)) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(10.),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(HighScoreScreen)
+        .insert(StateScoped(GameState::GameOver))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(title, TextStyle { font_size: TITLE_FONT_SIZE, color: Color::WHITE, ..default() }));
+            spawn_content(parent);
+        });
+}
+
+fn spawn_initials_screen(commands: &mut Commands) {
+    spawn_screen(commands, "NEW HIGH SCORE - ENTER YOUR INITIALS", |parent| {
+        parent.spawn((
+            TextBundle::from_section("", TextStyle { font_size: TITLE_FONT_SIZE, color: Color::WHITE, ..default() }),
+            InitialsText
+        ));
+    });
+}
+
+fn spawn_high_score_table(commands: &mut Commands, high_scores: &HighScores) {
+    spawn_screen(commands, "HIGH SCORES", |parent| {
+        let text_style = TextStyle { font_size: TABLE_FONT_SIZE, color: Color::WHITE, ..default() };
+
+        for (rank, entry) in high_scores.0.iter().enumerate() {
+            parent.spawn(TextBundle::from_section(
+                format!("{:>2}. {}  {:>7}  WAVE {:>2}  {}  {}", rank + 1, entry.name, entry.score, entry.wave, entry.date, entry.mode.as_str().to_uppercase()),
+                text_style.clone()
+            ));
+        }
+
+        parent.spawn(TextBundle::from_section("PRESS ENTER TO CONTINUE", TextStyle { font_size: PROMPT_FONT_SIZE, color: Color::WHITE, ..default() }));
+    });
+}
+
+fn get_high_scores_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|directory| directory.join(HIGH_SCORES_DIRECTORY).join(HIGH_SCORES_FILE))
+}
+
+fn load_high_scores() -> HighScores {
+    let Some(path) = get_high_scores_path() else {
+        warn!("No user data directory found, high scores will not be kept");
+        return HighScores::default();
+    };
+
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+            info!("No high score file at {} yet, starting with an empty table", path.display());
+            return HighScores::default();
+        },
+        Err(error) => {
+            warn!("Cannot read high scores from {}: {error}, starting with an empty table", path.display());
+            return HighScores::default();
+        }
+    };
+
+    let documents = match YamlLoader::load_from_str(&content) {
+        Ok(documents) => documents,
+        Err(error) => {
+            warn!("High score file {} is corrupted ({error}), starting with an empty table", path.display());
+            return HighScores::default();
+        }
+    };
+
+    let mut high_scores = HighScores::default();
+    for entry in documents.first().and_then(|document| document.as_vec()).into_iter().flatten() {
+        match parse_entry(entry) {
+            Some(entry) => high_scores.insert(entry),
+            None => warn!("Skipping malformed high score entry in {}: {entry:?}", path.display()),
+        }
+    }
+
+    high_scores
+}
+
+fn parse_entry(entry: &Yaml) -> Option<HighScoreEntry> {
+    Some(HighScoreEntry {
+        name: entry["name"].as_str()?.to_string(),
+        score: u32::try_from(entry["score"].as_i64()?).ok()?,
+        wave: usize::try_from(entry["wave"].as_i64()?).ok()?,
+        date: entry["date"].as_str()?.to_string(),
+        mode: GameMode::from_str(entry["mode"].as_str()?)?,
+    })
+}
+
+fn save_high_scores(high_scores: &HighScores) {
+    let Some(path) = get_high_scores_path() else {
+        return;
+    };
+
+    let entries = high_scores.0.iter()
+        .map(|entry| {
+            let mut hash = Hash::new();
+            hash.insert(Yaml::String("name".into()), Yaml::String(entry.name.clone()));
+            hash.insert(Yaml::String("score".into()), Yaml::Integer(entry.score as i64));
+            hash.insert(Yaml::String("wave".into()), Yaml::Integer(entry.wave as i64));
+            hash.insert(Yaml::String("date".into()), Yaml::String(entry.date.clone()));
+            hash.insert(Yaml::String("mode".into()), Yaml::String(entry.mode.as_str().into()));
+            Yaml::Hash(hash)
+        })
+        .collect();
+
+    let mut content = String::new();
+    if let Err(error) = YamlEmitter::new(&mut content).dump(&Yaml::Array(entries)) {
+        warn!("Cannot serialize high scores: {error}");
+        return;
+    }
+
+    let result = path.parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::write(&path, content));
+    if let Err(error) = result {
+        warn!("Cannot save high scores to {}: {error}", path.display());
+    }
+}
+
+/// Current UTC date as `YYYY-MM-DD`.
+fn today() -> String {
+    let days = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_secs() / 86_400) as i64;
+
+    // Civil date from a day count, see http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{year:04}-{month:02}-{day:02}")
+}
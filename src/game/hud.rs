@@ -0,0 +1,77 @@
+use bevy::prelude::*;
+
+use super::{player::Lives, score::Score, state::InGame, wave::Wave};
+
+// region:    --- Constants
+
+const HUD_FONT_SIZE: f32 = 24.;
+const HUD_MARGIN: f32 = 10.;
+// endregion: --- Constants
+
+// region:    --- Components
+#[derive(Component)]
+struct ScoreText;
+
+#[derive(Component)]
+struct LivesText;
+
+#[derive(Component)]
+struct WaveText;
+// endregion: --- Components
+
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(OnEnter(InGame), hud_spawn_system)
+            .add_systems(Update, (
+                update_score_text_system.run_if(resource_changed::<Score>),
+                update_lives_text_system.run_if(resource_changed::<Lives>),
+                update_wave_text_system.run_if(resource_exists_and_changed::<Wave>),
+            ).run_if(in_state(InGame)));
+    }
+}
+
+fn hud_spawn_system(mut commands: Commands) {
+    let text_style = TextStyle {
+        font_size: HUD_FONT_SIZE,
+        color: Color::WHITE,
+        ..default()
+    };
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(100.),
+                justify_content: JustifyContent::SpaceBetween,
+                padding: UiRect::all(Val::Px(HUD_MARGIN)),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(StateScoped(InGame))
+        .with_children(|parent| {
+            parent.spawn((TextBundle::from_section("SCORE 0", text_style.clone()), ScoreText));
+            parent.spawn((TextBundle::from_section("WAVE", text_style.clone()), WaveText));
+            parent.spawn((TextBundle::from_section("LIVES", text_style), LivesText));
+        });
+}
+
+fn update_score_text_system(score: Res<Score>, mut query: Query<&mut Text, With<ScoreText>>) {
+    for mut text in query.iter_mut() {
+        text.sections[0].value = format!("SCORE {}", score.points);
+    }
+}
+
+fn update_lives_text_system(lives: Res<Lives>, mut query: Query<&mut Text, With<LivesText>>) {
+    for mut text in query.iter_mut() {
+        text.sections[0].value = format!("LIVES {}", lives.0);
+    }
+}
+
+fn update_wave_text_system(wave: Res<Wave>, mut query: Query<&mut Text, With<WaveText>>) {
+    for mut text in query.iter_mut() {
+        text.sections[0].value = format!("WAVE {}", wave.get_number());
+    }
+}
@@ -0,0 +1,57 @@
+use bevy::{prelude::*, transform::TransformSystem};
+use bevy_rapier2d::prelude::RigidBody;
+
+use super::{components::{Ghost, InterpolatedTransform}, WinSize};
+
+/// Draws the bodies moved by the simulation between their last two ticks, so motion stays smooth
+/// whatever the ratio between the tick rate and the refresh rate.
+pub struct InterpolationPlugin;
+
+impl Plugin for InterpolationPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(FixedFirst, restore_simulated_transform_system)
+            .add_systems(FixedLast, (track_interpolated_system, record_simulated_transform_system).chain())
+            .add_systems(PostUpdate, interpolate_transform_system.before(TransformSystem::TransformPropagate));
+    }
+}
+
+/// Gives the simulation back the pose it computed, in place of the one blended for the last frame.
+fn restore_simulated_transform_system(mut query: Query<(&InterpolatedTransform, &mut Transform)>) {
+    for (interpolated, mut transform) in query.iter_mut() {
+        transform.set_if_neq(interpolated.current);
+    }
+}
+
+fn track_interpolated_system(
+    mut commands: Commands,
+    query: Query<(Entity, &Transform), (Or<(With<RigidBody>, With<Ghost>)>, Without<InterpolatedTransform>)>
+) {
+    for (entity, transform) in query.iter() {
+        commands.entity(entity).insert(InterpolatedTransform { previous: *transform, current: *transform });
+    }
+}
+
+fn record_simulated_transform_system(win_size: Res<WinSize>, mut query: Query<(&mut InterpolatedTransform, &Transform)>) {
+    for (mut interpolated, transform) in query.iter_mut() {
+        let offset = (transform.translation - interpolated.current.translation).truncate().abs();
+
+        // A jump of half the screen is a wrap to the opposite edge, not a move to draw across it.
+        interpolated.previous = if offset.x > win_size.width / 2. || offset.y > win_size.height / 2. {
+            *transform
+        } else {
+            interpolated.current
+        };
+        interpolated.current = *transform;
+    }
+}
+
+fn interpolate_transform_system(fixed_time: Res<Time<Fixed>>, mut query: Query<(&InterpolatedTransform, &mut Transform)>) {
+    let blend = fixed_time.overstep_fraction();
+
+    for (interpolated, mut transform) in query.iter_mut() {
+        transform.translation = interpolated.previous.translation.lerp(interpolated.current.translation, blend);
+        transform.rotation = interpolated.previous.rotation.slerp(interpolated.current.rotation, blend);
+        transform.scale = interpolated.current.scale;
+    }
+}
@@ -0,0 +1,51 @@
+use bevy::prelude::*;
+
+use super::{state::GameState, wave_file::WaveFile, GameMode};
+
+// region:    --- Constants
+
+const TITLE_FONT_SIZE: f32 = 60.;
+const PROMPT_FONT_SIZE: f32 = 24.;
+// endregion: --- Constants
+
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(OnEnter(GameState::MainMenu), main_menu_spawn_system)
+            .add_systems(Update, main_menu_input_system.run_if(in_state(GameState::MainMenu).and_then(resource_exists::<WaveFile>)));
+    }
+}
+
+fn main_menu_spawn_system(mut commands: Commands) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(20.),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(StateScoped(GameState::MainMenu))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section("RUST ASTEROIDS", TextStyle { font_size: TITLE_FONT_SIZE, color: Color::WHITE, ..default() }));
+            parent.spawn(TextBundle::from_section("PRESS ENTER FOR CLASSIC MODE", TextStyle { font_size: PROMPT_FONT_SIZE, color: Color::WHITE, ..default() }));
+            parent.spawn(TextBundle::from_section("PRESS E FOR ENDLESS MODE", TextStyle { font_size: PROMPT_FONT_SIZE, color: Color::WHITE, ..default() }));
+        });
+}
+
+fn main_menu_input_system(kb: Res<ButtonInput<KeyCode>>, mut game_mode: ResMut<GameMode>, mut next_state: ResMut<NextState<GameState>>) {
+    if kb.just_pressed(KeyCode::Enter) {
+        *game_mode = GameMode::Classic;
+        next_state.set(GameState::Playing);
+    } else if kb.just_pressed(KeyCode::KeyE) {
+        *game_mode = GameMode::Endless;
+        next_state.set(GameState::Playing);
+    }
+}
@@ -1,4 +1,4 @@
-use std::f32::consts::{PI, TAU};
+use std::f32::consts::PI;
 
 use bevy::{log::tracing_subscriber::field::debug, prelude::*};
 use bevy_rapier2d::{parry::simba::scalar::SupersetOf, prelude::{Collider, ColliderMassProperties, CollisionGroups, ExternalForce, Group, Restitution, RigidBody, Sleeping, Velocity}};
@@ -6,14 +6,212 @@ use rand::Rng;
 
 use crate::game::meteor;
 
-use super::{components::{Direction, FromPlayer, Laser, LaserTimer, LifeTime, Meteor, MeteorLevel, RocketDragTimer, RocketFire}, wave::Wave, DestroyedMeteors, GameTextures, WinSize, BASE_SPEED, LASER_SIZE, METEOR_SIZE, PLAYER_SIZE, SPRITE_SCALE, TIME_STEP };
+use super::{collision::CollisionLayer, placement::SpawnPlacement, rng::{GameRng, RngStream}, state::{field_active, GameState, InGame}, components::{CrackOverlay, Damage, Direction, Fragment, FromPlayer, Health, Hyperspace, Laser, LaserTimer, LifeTime, Meteor, MeteorLevel, MeteorType, Player, Weight, RocketDragTimer, RocketFire, ScreenWrap}, wave::{Wave, WaveSpawnSet}, DestroyedMeteors, GameTextures, WinSize, BASE_SPEED, COLLISION_DAMAGE, LASER_SIZE, METEOR_SIZE, PLAYER_SIZE, SPRITE_SCALE };
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MeteorKind {
+    Rocky,
+    Metallic,
+    Icy,
+    Explosive,
+    Magnetic,
+}
+
+impl MeteorKind {
+    pub const ALL: [MeteorKind; 5] = [MeteorKind::Rocky, MeteorKind::Metallic, MeteorKind::Icy, MeteorKind::Explosive, MeteorKind::Magnetic];
+
+    /// Numeric identifier used by the `kind` field of the wave file.
+    pub fn id(&self) -> i64 {
+        match self {
+            MeteorKind::Rocky => 1,
+            MeteorKind::Metallic => 2,
+            MeteorKind::Icy => 3,
+            MeteorKind::Explosive => 4,
+            MeteorKind::Magnetic => 5,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MeteorKind::Rocky => "rocky",
+            MeteorKind::Metallic => "metallic",
+            MeteorKind::Icy => "icy",
+            MeteorKind::Explosive => "explosive",
+            MeteorKind::Magnetic => "magnetic",
+        }
+    }
+
+    pub fn from_id(id: i64) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.id() == id)
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+
+    pub fn tint(&self) -> Color {
+        match self {
+            MeteorKind::Rocky => Color::WHITE,
+            MeteorKind::Metallic => Color::srgb(0.6, 0.65, 0.75),
+            MeteorKind::Icy => Color::srgb(0.6, 0.85, 1.),
+            MeteorKind::Explosive => Color::srgb(1., 0.45, 0.3),
+            MeteorKind::Magnetic => Color::srgb(0.75, 0.45, 1.),
+        }
+    }
+
+    /// Hit points per `MASS_PER_HIT_POINT` of collider mass.
+    pub fn toughness(&self) -> f32 {
+        match self {
+            MeteorKind::Metallic => 1.5,
+            MeteorKind::Icy | MeteorKind::Explosive => 0.8,
+            MeteorKind::Rocky | MeteorKind::Magnetic => 1.,
+        }
+    }
+
+    /// Fragments left behind when the meteor breaks, explosive meteors blowing up entirely.
+    pub fn fragments(&self) -> usize {
+        match self {
+            MeteorKind::Icy => 3,
+            MeteorKind::Explosive => 0,
+            MeteorKind::Rocky | MeteorKind::Metallic | MeteorKind::Magnetic => 2,
+        }
+    }
+
+    /// Angle the fragments fan out over, centred on the direction of the hit.
+    pub fn spread_angle(&self) -> f32 {
+        match self {
+            MeteorKind::Metallic => PI * 2. / 3.,
+            MeteorKind::Icy => PI * 4. / 3.,
+            MeteorKind::Rocky | MeteorKind::Explosive | MeteorKind::Magnetic => PI,
+        }
+    }
+
+    /// Multiplier applied to the authored weight to get the collider mass.
+    pub fn mass_factor(&self) -> f32 {
+        match self {
+            MeteorKind::Metallic => 2.,
+            MeteorKind::Magnetic => 1.5,
+            MeteorKind::Icy => 0.7,
+            MeteorKind::Rocky | MeteorKind::Explosive => 1.,
+        }
+    }
+
+    pub fn restitution(&self) -> f32 {
+        match self {
+            MeteorKind::Metallic => 0.5,
+            MeteorKind::Rocky | MeteorKind::Icy | MeteorKind::Explosive | MeteorKind::Magnetic => 1.,
+        }
+    }
+}
+
+/// Set off where an explosive meteor breaks, damaging meteors, ships and saucers within `BLAST_RADIUS`.
+#[derive(Event, Clone, Copy)]
+pub struct MeteorBlastEvent {
+    pub position: Vec3,
+    pub by_player: bool,
+}
+
+/// A meteor broken this frame, waiting for `child_meteor_spawn_system` to split it into fragments.
+#[derive(Debug, Clone)]
+pub struct BrokenMeteor {
+    pub kind: MeteorKind,
+    pub level: u8,
+    pub split: SplitRules,
+    pub weight: f32,
+    pub radius: f32,
+    pub position: Vec3,
+    pub velocity: Vec2,
+    pub angvel: f32,
+    /// Where the hit landed.
+    pub impact: Vec2,
+    /// Momentum handed over by the hit.
+    pub impulse: Vec2,
+}
+
+#[derive(Debug, Clone)]
 pub struct MeteorDefinition {
     pub weight: f32,
     pub speed: [f32; 2],
-    pub kind: u8,
+    pub kind: MeteorKind,
     pub level: u8,
+    pub split: SplitRules,
+}
+
+/// How a meteor and its fragments break apart, carried down every level of the split.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct SplitRules {
+    /// Fragments of this level no longer split.
+    pub max_level: u8,
+    /// Fragments per split, the kind's own count when `None`.
+    pub children: Option<usize>,
+    /// Size of a fragment relative to its parent, the classic `1 / level` shrink when `None`.
+    pub scale_per_level: Option<f32>,
+    /// Speed of the fragments relative to what the split alone gives them.
+    pub speed_per_level: f32,
+}
+
+impl Default for SplitRules {
+    fn default() -> Self {
+        Self { max_level: 3, children: None, scale_per_level: None, speed_per_level: 1. }
+    }
+}
+
+impl SplitRules {
+    pub fn fragments(&self, kind: MeteorKind) -> usize {
+        self.children.unwrap_or(kind.fragments())
+    }
+
+    pub fn scale(&self, level: u8) -> f32 {
+        match self.scale_per_level {
+            Some(scale_per_level) => SPRITE_SCALE * scale_per_level.powi(level.saturating_sub(1) as i32),
+            None => SPRITE_SCALE / level.max(1) as f32,
+        }
+    }
+}
+
+/// Where a scheduled meteor enters the field, the `edge` field of the wave file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpawnEdge {
+    /// Anywhere, with a preference for the edges.
+    Random,
+    Left,
+    Right,
+    Top,
+    Bottom,
+    /// Any of the four edges.
+    Edges,
+    Corners,
+    /// On a circle around the player, or as `Random` when there is no player.
+    Ring,
+}
+
+impl SpawnEdge {
+    pub const ALL: [SpawnEdge; 8] = [SpawnEdge::Random, SpawnEdge::Left, SpawnEdge::Right, SpawnEdge::Top, SpawnEdge::Bottom, SpawnEdge::Edges, SpawnEdge::Corners, SpawnEdge::Ring];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SpawnEdge::Random => "random",
+            SpawnEdge::Left => "left",
+            SpawnEdge::Right => "right",
+            SpawnEdge::Top => "top",
+            SpawnEdge::Bottom => "bottom",
+            SpawnEdge::Edges => "edges",
+            SpawnEdge::Corners => "corners",
+            SpawnEdge::Ring => "ring",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|edge| edge.name() == name)
+    }
+}
+
+/// One entry of a wave timeline: the meteor, when it appears (seconds since the wave started) and where.
+#[derive(Debug, Clone)]
+pub struct MeteorSpawn {
+    pub at: f32,
+    pub edge: SpawnEdge,
+    pub meteor: MeteorDefinition,
 }
 
 #[derive(Debug)]
@@ -23,19 +221,41 @@ struct MeteorMapper {
     linvel: Vec2,
     angvel: f32,
     restitution_coefficient: f32,
-    kind: u8,
+    kind: MeteorKind,
     level: u8,
+    split: SplitRules,
 }
 
+pub const BLAST_RADIUS: f32 = 150.;
+pub const BLAST_DAMAGE: Damage = Damage(2.);
+/// Momentum handed to a meteor at the centre of a blast, fading to 0 at `BLAST_RADIUS`.
+pub const BLAST_IMPULSE: f32 = 3000.;
+/// Speed at which fragments move apart from each other, on top of the speed of the broken meteor.
+const FRAGMENT_SEPARATION_SPEED: f32 = 80.;
+const MASS_PER_HIT_POINT: f32 = 20.;
+const MAGNET_RADIUS: f32 = 300.;
+/// Acceleration with which a magnetic meteor drags the ship when right next to it, fading to 0 at `MAGNET_RADIUS`.
+const MAGNET_STRENGTH: f32 = 120.;
+/// Pixels per second for one unit of speed in the wave file.
+const SPEED_UNIT: f32 = BASE_SPEED / 60.;
+
+/// Spawns the fragments queued in `DestroyedMeteors`; anything counting the meteors left runs after it.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FragmentSpawnSet;
+
 pub struct MeteorPlugin;
 
 impl Plugin for MeteorPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, (
-            meteor_spawn_system.run_if(enough_meteors_to_spawn),
-            child_meteor_spawn_system.run_if(meteors_destroyed),
-            adjust_meteor_speed_system
-        ));
+        app
+            .add_event::<MeteorBlastEvent>()
+            .add_systems(FixedUpdate, meteor_spawn_system.run_if(in_state(GameState::Playing).and_then(enough_meteors_to_spawn)).in_set(WaveSpawnSet))
+            .add_systems(FixedUpdate, (
+                child_meteor_spawn_system.run_if(meteors_destroyed).in_set(FragmentSpawnSet),
+                adjust_meteor_speed_system,
+                magnetic_pull_system,
+            ).run_if(field_active))
+            .add_systems(Update, crack_overlay_system.run_if(field_active));
     }
 }
 
@@ -43,32 +263,49 @@ fn enough_meteors_to_spawn(wave_resource: Res<Wave>) -> bool {
     wave_resource.has_meteors()
 }
 
+/// Plays the wave timeline: every entry whose time has come is spawned, the others wait for a later frame.
 fn meteor_spawn_system(
     mut commands: Commands,
+    time: Res<Time>,
     win_size: Res<WinSize>,
     mut wave_resource: ResMut<Wave>,
-    game_textures: Res<GameTextures>
+    mut game_rng: ResMut<GameRng>,
+    game_textures: Res<GameTextures>,
+    query_player: Query<&Transform, With<Player>>,
+    query_meteor: Query<&Transform, With<Meteor>>
 ) {
-    let meteor_to_spawn = get_meteor_definition_mapped(&win_size, wave_resource.get_meteors().pop().unwrap());
-    spawn_meteor(&mut commands, &game_textures, meteor_to_spawn);
+    let meteors_to_spawn = wave_resource.take_due_meteors(time.delta_seconds());
+    if meteors_to_spawn.is_empty() {
+        return;
+    }
+
+    let player_position = query_player.get_single().ok().map(|transform| transform.translation.truncate());
+    let mut placement = SpawnPlacement::new(&win_size, player_position, query_meteor.iter().map(|transform| transform.translation.truncate()));
+
+    let rng = game_rng.stream(RngStream::Meteors);
+    for meteor_spawn in meteors_to_spawn {
+        let meteor_to_spawn = get_meteor_definition_mapped(&mut placement, rng, meteor_spawn);
+        spawn_meteor(&mut commands, &game_textures, meteor_to_spawn);
+    }
 }
 
-fn get_meteor_definition_mapped(win_size: &Res<WinSize>, meteor_definition: MeteorDefinition) -> MeteorMapper {
+fn get_meteor_definition_mapped(placement: &mut SpawnPlacement, rng: &mut impl Rng, meteor_spawn: MeteorSpawn) -> MeteorMapper {
+    let meteor_definition = meteor_spawn.meteor;
+    let init_position = placement.place(rng, meteor_spawn.edge);
+    let speed = [
+        meteor_definition.speed[0] * SPEED_UNIT,
+        meteor_definition.speed[1] * SPEED_UNIT
+    ];
+
     MeteorMapper { 
-        init_position: Vec3 { 
-            x: win_size.width * rand::thread_rng().gen_range(-1.0..=1.0),
-            y: win_size.height * rand::thread_rng().gen_range(-1.0..=1.0),
-            z: 10. 
-        },
+        init_position: init_position.extend(10.),
         weight: meteor_definition.weight,
-        linvel: Vec2 { 
-            x: rand::thread_rng().gen_range((meteor_definition.speed[0] * TIME_STEP * BASE_SPEED)..=((meteor_definition.speed[1] * TIME_STEP * BASE_SPEED))) * rand::thread_rng().gen_range(-1.0..=1.0),
-            y: rand::thread_rng().gen_range((meteor_definition.speed[0] * TIME_STEP * BASE_SPEED)..=((meteor_definition.speed[1] * TIME_STEP * BASE_SPEED))) * rand::thread_rng().gen_range(-1.0..=1.0)
-        },
-        angvel: rand::thread_rng().gen_range((0.)..PI),
-        restitution_coefficient: 1.,
+        linvel: placement.velocity(rng, init_position, speed),
+        angvel: rng.gen_range((0.)..PI),
+        restitution_coefficient: meteor_definition.kind.restitution(),
         kind: meteor_definition.kind,
-        level: meteor_definition.level
+        level: meteor_definition.level,
+        split: meteor_definition.split
     }
 }
 
@@ -79,84 +316,120 @@ fn meteors_destroyed(destroyed_meteors: Res<DestroyedMeteors>) -> bool {
 fn child_meteor_spawn_system(mut commands: Commands, game_textures: Res<GameTextures>, mut destroyed_meteors: ResMut<DestroyedMeteors>) {
     let meteors = std::mem::take(&mut destroyed_meteors.0);
     
-    for (meteor_definition, translation) in meteors {
-        let meteors_to_spawn = get_meteors(translation, meteor_definition);
-
-        for meteor in meteors_to_spawn {
-            spawn_meteor(&mut commands, &game_textures, meteor);
+    for broken_meteor in meteors {
+        for meteor in get_fragments(&broken_meteor) {
+            let fragment = spawn_meteor(&mut commands, &game_textures, meteor);
+            commands.entity(fragment).insert(Fragment);
         }
     }
 }
 
-fn spawn_meteor(commands: &mut Commands, game_textures: &Res<GameTextures>, meteor: MeteorMapper) {
+fn spawn_meteor(commands: &mut Commands, game_textures: &Res<GameTextures>, meteor: MeteorMapper) -> Entity {
+    let mass = meteor.weight * meteor.kind.mass_factor();
+
     commands
         .spawn(SpriteBundle {
             texture: game_textures.meteor.clone(),
             sprite: Sprite {
+                color: meteor.kind.tint(),
                 ..Default::default()
             },
             transform: Transform {
                 translation: meteor.init_position,
-                scale: Vec3::new(SPRITE_SCALE / meteor.level as f32, SPRITE_SCALE / meteor.level as f32, 1.),
+                scale: Vec3::new(meteor.split.scale(meteor.level), meteor.split.scale(meteor.level), 1.),
                 ..Default::default()
             },
             ..Default::default()
         })
         .insert(Meteor)
         .insert(MeteorLevel(meteor.level))
+        .insert(meteor.split)
+        .insert(MeteorType(meteor.kind))
+        .insert(Health::new(mass * meteor.kind.toughness() / MASS_PER_HIT_POINT))
+        .insert(Damage(COLLISION_DAMAGE))
+        .insert(Weight(meteor.weight))
+        .insert(ScreenWrap)
+        .insert(StateScoped(InGame))
         .insert(RigidBody::Dynamic)
         .insert(Collider::ball((METEOR_SIZE.0 / 2.)))
-        .insert(ColliderMassProperties::Mass(meteor.weight))
+        .insert(CollisionLayer::Meteor.collision_groups())
+        .insert(ColliderMassProperties::Mass(mass))
         .insert(Velocity {
             linvel: meteor.linvel,
             angvel: meteor.angvel,
         })
         .insert(Restitution::coefficient(meteor.restitution_coefficient))
         .insert(ExternalForce::default())
-        .insert(Sleeping::disabled());
+        .insert(Sleeping::disabled())
+        .with_children(|parent| {
+            parent
+                .spawn(SpriteBundle {
+                    texture: game_textures.meteor_cracks[0].clone(),
+                    transform: Transform::from_xyz(0., 0., 0.1),
+                    visibility: Visibility::Hidden,
+                    ..Default::default()
+                })
+                .insert(CrackOverlay);
+        })
+        .id()
 }
 
-fn get_meteors(translation: Vec3, meteor_definition: MeteorDefinition) -> Vec<MeteorMapper> {
-    const CHILDREN_METEORS_COUNTER: i32 = 2;
-    let mut meteors = Vec::new();
-
-    for count in 0..CHILDREN_METEORS_COUNTER {
-        let new_direction = get_new_meteor_direction(&translation, &meteor_definition.speed, count as f32);
-        
-        let init_position = get_init_position(&translation, new_direction.clone().extend(0.));
-
-        let meteor_mapper = MeteorMapper { 
-            init_position,
-            weight: meteor_definition.weight / 2.,
-            linvel: get_minimum_speed_value(&(new_direction * 0.75)),
-            angvel: rand::thread_rng().gen_range((0.)..PI),
-            restitution_coefficient: 1.,
-            kind: meteor_definition.kind,
-            level: meteor_definition.level + 1
-        };
-
-        meteors.push(meteor_mapper);
+/// Splits a broken meteor so that its fragments carry the momentum of the meteor plus the one of the hit.
+///
+/// Fragments fan out over the kind's spread angle around the direction of the hit, far enough apart
+/// not to overlap. Their separation speeds are offset by their mean so they cancel out, and an
+/// off-centre hit adds to the spin.
+/// A `speed_per_level` other than 1 scales the result, trading exactness for the wave's pacing.
+fn get_fragments(broken_meteor: &BrokenMeteor) -> Vec<MeteorMapper> {
+    let count = broken_meteor.split.fragments(broken_meteor.kind);
+    if count == 0 {
+        return Vec::new();
     }
 
-    meteors
-}
+    let mass = broken_meteor.weight * broken_meteor.kind.mass_factor();
+    let center_velocity = (broken_meteor.velocity * mass + broken_meteor.impulse) / mass;
 
-fn get_new_meteor_direction(translation: &Vec3, actual_meteor_direction: &[f32; 2], count: f32) -> Vec2 {
-    const ANGLE_OFFSET_W_CURRENT_DIRECTION: f32 = PI / 2.0;
-    const ANGLE_OFFSET_BTW_NEW_DIRECTIONS: f32 = PI;
+    let base_direction = [broken_meteor.impulse, broken_meteor.velocity].into_iter()
+        .find(|direction| *direction != Vec2::ZERO)
+        .map_or(0., Vec2::to_angle);
+    let directions: Vec<Vec2> = (0..count)
+        .map(|index| {
+            let offset = if count == 1 { 0. } else { index as f32 / (count - 1) as f32 - 0.5 };
+            Vec2::from_angle(base_direction + broken_meteor.kind.spread_angle() * offset)
+        })
+        .collect();
+    let mean_direction = directions.iter().sum::<Vec2>() / count as f32;
 
-    let mut new_direction = Vec2::from_array(*actual_meteor_direction);
-    new_direction.x = (new_direction.x - translation.x) * (ANGLE_OFFSET_W_CURRENT_DIRECTION + ANGLE_OFFSET_BTW_NEW_DIRECTIONS * count).cos() - (new_direction.y - translation.y) * (ANGLE_OFFSET_W_CURRENT_DIRECTION + ANGLE_OFFSET_BTW_NEW_DIRECTIONS * count).sin() + new_direction.x;
-    new_direction.y = (new_direction.x - translation.x) * (ANGLE_OFFSET_W_CURRENT_DIRECTION + ANGLE_OFFSET_BTW_NEW_DIRECTIONS * count).sin() - (new_direction.y - translation.y) * (ANGLE_OFFSET_W_CURRENT_DIRECTION + ANGLE_OFFSET_BTW_NEW_DIRECTIONS * count).cos() + new_direction.y;
+    // Neighbours are `spread / (count - 1)` apart, so their centres must sit this far out to leave room for both.
+    let fragment_radius = broken_meteor.radius * broken_meteor.split.scale(broken_meteor.level + 1) / broken_meteor.split.scale(broken_meteor.level);
+    let distance = if count == 1 {
+        broken_meteor.radius / 2.
+    } else {
+        let half_step = broken_meteor.kind.spread_angle() / (count - 1) as f32 / 2.;
+        (fragment_radius / half_step.sin()).max(broken_meteor.radius / 2.)
+    };
 
-    new_direction
-}
+    let position = broken_meteor.position.truncate();
+    let lever = (broken_meteor.impact - position).clamp_length_max(broken_meteor.radius);
+    let inertia = 0.5 * mass * broken_meteor.radius.powi(2);
+    let angvel = broken_meteor.angvel + lever.perp_dot(broken_meteor.impulse) / inertia.max(f32::EPSILON);
 
-fn get_init_position(translation: &Vec3, new_direction: Vec3) -> Vec3 {
-    translation.clone() + (new_direction.normalize() * Vec3 { x: 10., y: 10., z: 1. })
+    directions.into_iter()
+        .map(|direction| MeteorMapper {
+            init_position: (position + direction * distance).extend(broken_meteor.position.z),
+            weight: broken_meteor.weight / count as f32,
+            linvel: (center_velocity + (direction - mean_direction) * FRAGMENT_SEPARATION_SPEED) * broken_meteor.split.speed_per_level,
+            angvel,
+            restitution_coefficient: broken_meteor.kind.restitution(),
+            kind: broken_meteor.kind,
+            level: broken_meteor.level + 1,
+            split: broken_meteor.split
+        })
+        .collect()
 }
 
-fn adjust_meteor_speed_system(mut query_meteor: Query<&mut Velocity, With<Meteor>>) {
+/// Keeps wave meteors moving; fragments keep the speed their split gave them.
+fn adjust_meteor_speed_system(mut query_meteor: Query<&mut Velocity, (With<Meteor>, Without<Fragment>)>) {
     for mut velocity in query_meteor.iter_mut() {
         velocity.linvel = get_minimum_speed_value(&velocity.linvel);
     }
@@ -172,4 +445,51 @@ fn get_minimum_speed_value(speed: &Vec2) -> Vec2 {
         direction * SPEED_MIN
     }
 
-}
\ No newline at end of file
+}
+
+/// Drags the ship towards nearby magnetic meteors.
+fn magnetic_pull_system(
+    time: Res<Time>,
+    query_meteor: Query<(&Transform, &MeteorType), With<Meteor>>,
+    mut query_player: Query<(&Transform, &mut Velocity), (With<Player>, Without<Hyperspace>)>
+) {
+    let Ok((player_transform, mut velocity)) = query_player.get_single_mut() else {
+        return;
+    };
+
+    let player_position = player_transform.translation.truncate();
+    let pull: Vec2 = query_meteor.iter()
+        .filter(|(_, meteor_type)| meteor_type.0 == MeteorKind::Magnetic)
+        .map(|(transform, _)| transform.translation.truncate() - player_position)
+        .filter(|offset| offset.length() < MAGNET_RADIUS)
+        .map(|offset| offset.normalize_or_zero() * MAGNET_STRENGTH * (1. - offset.length() / MAGNET_RADIUS))
+        .sum();
+
+    velocity.linvel += pull * time.delta_seconds();
+}
+
+/// Shows more cracks on a meteor as its health goes down.
+fn crack_overlay_system(
+    game_textures: Res<GameTextures>,
+    query_meteor: Query<(&Health, &Children), (With<Meteor>, Changed<Health>)>,
+    mut query_overlay: Query<(&mut Handle<Image>, &mut Visibility), With<CrackOverlay>>
+) {
+    let stages = game_textures.meteor_cracks.len();
+
+    for (health, children) in &query_meteor {
+        let stage = ((1. - health.fraction()) * stages as f32).ceil() as usize;
+
+        for child in children {
+            let Ok((mut texture, mut visibility)) = query_overlay.get_mut(*child) else {
+                continue;
+            };
+
+            if stage == 0 {
+                *visibility = Visibility::Hidden;
+            } else {
+                *texture = game_textures.meteor_cracks[stage.min(stages) - 1].clone();
+                *visibility = Visibility::Inherited;
+            }
+        }
+    }
+}
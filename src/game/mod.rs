@@ -1,17 +1,49 @@
 #![allow(unused)]
 mod player;
 mod meteor;
+mod enemy;
+mod weapon;
+mod wrap;
+mod score;
+mod hud;
+mod highscore;
+mod state;
+mod menu;
 mod components;
 mod wave;
-
-use std::collections::HashSet;
-
-use bevy::{core::FrameCount, diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin}, ecs::entity, input::gamepad::{self, ButtonSettingsError}, math::Vec3Swizzles, prelude::*, sprite::MaterialMesh2dBundle, window::{self, PresentMode, PrimaryWindow, WindowTheme}};
-use bevy_rapier2d::{plugin::RapierConfiguration, prelude::{ ColliderMassProperties, CollisionEvent, ContactForceEvent, ExternalForce, RigidBody, Velocity }};
-use components::{Direction, Enemy, Explosion, ExplosionTimer, ExplosionToSpawn, FromEnemy, FromPlayer, Laser, LaserTimer, LifeTime, Meteor, MeteorLevel, Player, RocketDragTimer};
+mod wave_file;
+mod placement;
+mod explosion;
+mod collision;
+mod interpolation;
+mod rng;
+mod controls;
+mod replay;
+
+pub use wave_file::validate_wave_file;
+pub use rng::daily_seed;
+pub use replay::Replay;
+
+use bevy::{core::FrameCount, diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin}, ecs::{entity, system::ParamSet}, input::gamepad::{self, ButtonSettingsError}, math::Vec3Swizzles, prelude::*, sprite::MaterialMesh2dBundle, window::{self, PresentMode, PrimaryWindow, WindowTheme}};
+use bevy_rapier2d::{plugin::{RapierConfiguration, TimestepMode}, prelude::{ ColliderMassProperties, ContactForceEvent, ExternalForce, RigidBody, Velocity }};
+use components::{Direction, Enemy, Explosion, ExplosionTimer, ExplosionToSpawn, FromEnemy, FromPlayer, Ghost, Laser, LaserTimer, Damage, Health, LifeTime, Meteor, MeteorLevel, MeteorType, Player, RocketDragTimer, Weight};
 use player::PlayerPlugin;
-use meteor::{MeteorDefinition, MeteorPlugin};
-use wave::Wave;
+use enemy::EnemyPlugin;
+use weapon::WeaponPlugin;
+use wrap::{resolve_ghost, ScreenWrapPlugin};
+use score::{meteor_points, Score, ScorePlugin};
+use hud::HudPlugin;
+use explosion::ExplosionPlugin;
+use collision::{collision_dispatch_system, CollisionPlugin, LaserHitMeteorEvent};
+use interpolation::InterpolationPlugin;
+use rng::RngPlugin;
+use controls::ControlsPlugin;
+use replay::ReplayPlugin;
+use highscore::HighScorePlugin;
+use state::{field_active, GameState, InGame, StatePlugin};
+use menu::MenuPlugin;
+use meteor::{BrokenMeteor, MeteorBlastEvent, MeteorKind, MeteorPlugin, SplitRules, BLAST_DAMAGE, BLAST_IMPULSE, BLAST_RADIUS};
+use wave::{Wave, WavePlugin};
 
 
 
@@ -28,6 +60,15 @@ const ROCKET_FIRE_SIZE: (f32, f32) = (2000., 2000.);
 
 const METEOR_SPRITE: &str = "meteore1.png";
 const METEOR_SIZE: (f32, f32) = (147., 119.);
+const METEOR_CRACKS_SPRITES: [&str; 3] = ["meteor_cracks_1.png", "meteor_cracks_2.png", "meteor_cracks_3.png"];
+
+const EXPLOSION_SHEET: &str = "explosion.png";
+const EXPLOSION_FRAME_SIZE: f32 = 64.;
+const EXPLOSION_COLUMNS: u32 = 4;
+const EXPLOSION_ROWS: u32 = 4;
+const EXPLOSION_LEN: usize = 16;
+
+const FLASH_SPRITE: &str = "flash.png";
 
 const SPRITE_SCALE: f32 = 0.5;
 
@@ -35,12 +76,16 @@ const SPRITE_SCALE: f32 = 0.5;
 
 // region:    --- Game Constants
 
-const MARGIN: f32 = 100.;
-
-const TIME_STEP: f32 = 1./60.;
+/// Simulation ticks per second when no `--tick-rate` is given.
+pub const DEFAULT_TICK_RATE: f64 = 60.;
 const BASE_SPEED: f32 = 500.;
 
 const ENEMY_MAX: u32 = 2;
+
+/// Damage dealt by a body ramming another, enough to break any ship.
+const COLLISION_DAMAGE: f32 = 10.;
+/// Momentum a laser hands over to the meteor it hits, in mass units times pixels per second.
+const LASER_IMPULSE: f32 = 1000.;
 // endregion: --- Game Constants
 
 // region:     --- Resources
@@ -56,35 +101,100 @@ struct GameTextures {
 	laser: Handle<Image>,
 	rocket_fire: Handle<Image>,
 	meteor: Handle<Image>,
+	meteor_cracks: [Handle<Image>; 3],
+	explosion: Handle<Image>,
+	explosion_layout: Handle<TextureAtlasLayout>,
+	flash: Handle<Image>,
 }
 
-#[derive(Resource)]
-struct DestroyedMeteors(pub Vec<(MeteorDefinition, Vec3)>);
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GameMode {
+	/// The game ends once the last wave of the wave file is cleared.
+	Classic,
+	/// Generated waves of rising difficulty follow the ones from the wave file.
+	Endless,
+}
+
+impl GameMode {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			GameMode::Classic => "classic",
+			GameMode::Endless => "endless",
+		}
+	}
+
+	pub fn from_str(value: &str) -> Option<Self> {
+		match value {
+			"classic" => Some(GameMode::Classic),
+			"endless" => Some(GameMode::Endless),
+			_ => None,
+		}
+	}
+}
 
 #[derive(Resource)]
-struct Fragments(pub Vec<Vec3>);
+struct DestroyedMeteors(pub Vec<BrokenMeteor>);
+
+/// Gameplay and physics ticks per second, set from `GamePlugin::tick_rate`.
+#[derive(Resource, Clone, Copy)]
+pub struct TickRate(pub f64);
+
 
 // endregion:  --- Resources
 
-pub struct GamePlugin;
+/// Gameplay runs in `FixedUpdate`, `tick_rate` times per second whatever the refresh rate.
+/// Every game plays from `seed` when one is given, from a new random seed otherwise.
+/// Games are recorded to `record` when set, and `replay` is played back instead of the keyboard.
+pub struct GamePlugin {
+	pub tick_rate: f64,
+	pub seed: Option<u64>,
+	pub record: Option<std::path::PathBuf>,
+	pub replay: Option<Replay>,
+}
+
+impl Default for GamePlugin {
+	fn default() -> Self {
+		Self { tick_rate: DEFAULT_TICK_RATE, seed: None, record: None, replay: None }
+	}
+}
 
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
         app
 		.register_type::<MeteorLevel>()
+		.insert_resource(Time::<Fixed>::from_hz(self.tick_rate))
+		.insert_resource(TickRate(self.tick_rate))
+        .add_plugins(InterpolationPlugin)
+        .add_plugins(RngPlugin { seed: self.seed })
+        .add_plugins(StatePlugin)
+        .add_plugins(ControlsPlugin)
+        .add_plugins(ReplayPlugin { tick_rate: self.tick_rate, record: self.record.clone(), replay: self.replay.clone() })
+        .add_plugins(CollisionPlugin)
+        .add_plugins(MenuPlugin)
+        .add_plugins(WavePlugin)
         .add_plugins(PlayerPlugin)
         .add_plugins(MeteorPlugin)
+        .add_plugins(EnemyPlugin)
+        .add_plugins(WeaponPlugin)
+        .add_plugins(ScreenWrapPlugin)
+        .add_plugins(ScorePlugin)
+        .add_plugins(HudPlugin)
+        .add_plugins(HighScorePlugin)
+        .add_plugins(ExplosionPlugin)
+        .insert_resource(GameMode::Classic)
         .add_systems(Startup, setup_system)
-		.add_systems(PostStartup, init_wave_system)
+		.add_systems(OnEnter(InGame), clear_destruction_queues_system)
 		.add_systems(Update, make_visible)
-		.add_systems(Update, (correction_screen_overflow_system, check_life_time_system, handle_fire_events_system));
+		.add_systems(FixedUpdate, (check_life_time_system, laser_hit_meteor_system.after(collision_dispatch_system), meteor_blast_system).run_if(field_active));
     }
 }
 
 fn setup_system(
 	mut commands: Commands,
 	asset_server: Res<AssetServer>,
+	mut texture_atlases: ResMut<Assets<TextureAtlasLayout>>,
 	mut windows:  Query<&mut Window, With<PrimaryWindow>>,
+	tick_rate: Res<TickRate>,
 	mut rapier_configuration: ResMut<RapierConfiguration>
 ) {
 	// camera
@@ -103,19 +213,25 @@ fn setup_system(
 		player: asset_server.load(PLAYER_SPRITE),
 		laser: asset_server.load(LASER_SPRITE),
 		rocket_fire: asset_server.load(ROCKET_FIRE_SPRITE),
-		meteor: asset_server.load(METEOR_SPRITE)
+		meteor: asset_server.load(METEOR_SPRITE),
+		meteor_cracks: METEOR_CRACKS_SPRITES.map(|sprite| asset_server.load(sprite)),
+		explosion: asset_server.load(EXPLOSION_SHEET),
+		explosion_layout: texture_atlases.add(TextureAtlasLayout::from_grid(UVec2::splat(EXPLOSION_FRAME_SIZE as u32), EXPLOSION_COLUMNS, EXPLOSION_ROWS, None, None)),
+		flash: asset_server.load(FLASH_SPRITE)
 	 };
 	commands.insert_resource(game_textures);
 
 	commands.insert_resource(DestroyedMeteors(Vec::new()));
-	commands.insert_resource(Fragments(Vec::new()));
 
 	// cancel gravity effect
     rapier_configuration.gravity = Vec2::new(0., 0.);
+
+	// one physics step per gameplay tick
+	rapier_configuration.timestep_mode = TimestepMode::Fixed { dt: 1. / tick_rate.0 as f32, substeps: 1 };
 }
 
-fn init_wave_system(mut commands: Commands) {
-	commands.insert_resource(Wave::new());
+fn clear_destruction_queues_system(mut destroyed_meteors: ResMut<DestroyedMeteors>) {
+	destroyed_meteors.0.clear();
 }
 
 fn make_visible(mut window: Query<&mut Window>, frames: Res<FrameCount>) {
@@ -128,25 +244,6 @@ fn make_visible(mut window: Query<&mut Window>, frames: Res<FrameCount>) {
     }
 }
 
-fn correction_screen_overflow_system(win_size: Res<WinSize>, mut query: Query<&mut Transform>) {
-    for mut transform in query.iter_mut() {
-        let translation = &mut transform.translation;
-
-		let new_position = |p: f32, screen_limit: f32| -> f32 {
-			if p > screen_limit {
-				-screen_limit
-			} else if p < -screen_limit {
-				screen_limit
-			} else {
-				p
-			}
-		};
-
-		translation.x = new_position(translation.x, win_size.width / 2. + MARGIN);
-		translation.y = new_position(translation.y, win_size.height / 2. + MARGIN);
-    }
-}
-
 fn check_life_time_system(mut commands: Commands, time: Res<Time>, mut query: Query<(Entity, &mut LifeTime)>) {
     for (entity, mut life_time) in query.iter_mut() {
 		life_time.0.tick(time.delta());
@@ -156,102 +253,116 @@ fn check_life_time_system(mut commands: Commands, time: Res<Time>, mut query: Qu
     }
 }
 
-fn handle_fire_events_system(
+fn laser_hit_meteor_system(
 	mut commands: Commands,
-	mut fragments: ResMut<Fragments>,
 	mut destroyed_meteors: ResMut<DestroyedMeteors>,
-	mut collision_events: EventReader<CollisionEvent>,
-	query_meteor: Query<(Entity, &MeteorLevel, &ColliderMassProperties, &Velocity, &Transform), With<Meteor>>,
-	query_laser: Query<(Entity, &Velocity), With<Laser>>
+	mut score: ResMut<Score>,
+	mut laser_hit_meteor_events: EventReader<LaserHitMeteorEvent>,
+	mut blast_events: EventWriter<MeteorBlastEvent>,
+	mut query_meteor: Query<(&MeteorLevel, &MeteorType, &SplitRules, &Weight, &mut Health, &Velocity, &Transform), With<Meteor>>,
+	query_laser: Query<(&Velocity, &Transform, &Damage, Has<FromPlayer>), With<Laser>>
 ) {
-    let mut entities_whose_collision_event_is_processed = HashSet::new();
-
-	'outer: for collision_event in collision_events.read() {
-		
-		let (entity_a, entity_b) = match get_entities_touched(collision_event, &mut entities_whose_collision_event_is_processed) {
-			None => continue,
-			Some((entity_a, entity_b)) => (entity_a, entity_b)
+	for hit in laser_hit_meteor_events.read() {
+		let Ok((laser_velocity, laser_transform, laser_damage, fired_by_player)) = query_laser.get(hit.laser) else {
+			continue;
+		};
+		let Ok((meteor_level, meteor_type, split, weight, mut health, velocity, transform)) = query_meteor.get_mut(hit.meteor) else {
+			continue;
 		};
+		commands.entity(hit.laser).despawn();
 
-		let mut laser_direction = None;
-		for (entity_laser, velocity) in &query_laser {
-			if entity_laser == entity_a || entity_laser == entity_b {
-				let x = if velocity.linvel.x > 0. { 1. } else { -1. };
-				let y = if velocity.linvel.y > 0. { 1. } else { -1. };
-				laser_direction = Some(Vec2 {x, y});
-			}
+		// Heavy and tough meteors soak up a few hits, a meteor broken earlier this frame takes none.
+		if !health.take(laser_damage) {
+			continue;
 		}
 
-		for (entity_meteor, meteor_level, mass, velocity, transform) in &query_meteor {
-			if entity_meteor == entity_a || entity_meteor == entity_b {
-				let meteor_velocity = apply_laser_direction_on_meteor(velocity, laser_direction.unwrap());
-				handle_entity_destruction(&mut fragments, &mut destroyed_meteors, meteor_level, mass, meteor_velocity, transform);
-				commands.entity(entity_a).despawn();
-				commands.entity(entity_b).despawn();
-				break 'outer;
-			}
+		let impact = laser_transform.translation.truncate();
+		let impulse = laser_velocity.linvel.normalize_or_zero() * LASER_IMPULSE;
+		let score = if fired_by_player { Some(&mut score) } else { None };
+		if let Some(blast) = handle_entity_destruction(&mut commands, &mut destroyed_meteors, score, meteor_level, meteor_type, split, weight, velocity, impact, impulse, transform) {
+			blast_events.send(blast);
 		}
-    }
+		commands.entity(hit.meteor).despawn_recursive();
+	}
 }
 
-fn apply_laser_direction_on_meteor(velocity: &Velocity, laser_direction: Vec2) -> Vec2 {
-	let direction = |meteor_direction, laser_direction| -> f32 {
-		if meteor_direction > 0. {
-			if laser_direction > 0. {
-				meteor_direction
-			} else {
-				meteor_direction * -1.
-			}
-		} else {
-			if laser_direction > 0. {
-				meteor_direction * -1.
-			} else {
-				meteor_direction
-			}
-		}
-	};
+/// Applies the blasts of explosive meteors to the meteors around them, which may set off further blasts.
+/// Ships and saucers caught in a blast are handled by their own plugins.
+fn meteor_blast_system(
+	mut commands: Commands,
+	mut destroyed_meteors: ResMut<DestroyedMeteors>,
+	mut score: ResMut<Score>,
+	mut blast_events: ParamSet<(EventReader<MeteorBlastEvent>, EventWriter<MeteorBlastEvent>)>,
+	mut query_meteor: Query<(Entity, &MeteorLevel, &MeteorType, &SplitRules, &Weight, &mut Health, &Velocity, &Transform), With<Meteor>>
+) {
+	let blasts: Vec<MeteorBlastEvent> = blast_events.p0().read().copied().collect();
+	let mut chained_blasts = Vec::new();
 
-	Vec2 { x: direction(velocity.linvel.x, laser_direction.x), y: direction(velocity.linvel.y, laser_direction.y) }
-}
+	for blast in blasts {
+		for (entity_meteor, meteor_level, meteor_type, split, weight, mut health, velocity, transform) in &mut query_meteor {
+			let offset = (transform.translation - blast.position).truncate();
+
+			if offset.length() > BLAST_RADIUS || !health.take(&BLAST_DAMAGE) {
+				continue;
+			}
 
-fn get_entities_touched(collision_event: &CollisionEvent, entities_whose_collision_event_is_processed: &mut HashSet<Entity>) -> Option<(Entity, Entity)> {
-	if let CollisionEvent::Started(entity_a, entity_b, _) = collision_event {
-		if entities_whose_collision_event_is_processed.contains(entity_a) || entities_whose_collision_event_is_processed.contains(entity_b) {
-			None
-		} else {
-			entities_whose_collision_event_is_processed.insert(entity_a.clone());
-			entities_whose_collision_event_is_processed.insert(entity_b.clone());
-			Some((entity_a.clone(), entity_b.clone()))
+			// The shock wave pushes harder on the meteors closest to its centre.
+			let impulse = offset.normalize_or_zero() * BLAST_IMPULSE * (1. - offset.length() / BLAST_RADIUS);
+			let score = if blast.by_player { Some(&mut score) } else { None };
+			if let Some(chained_blast) = handle_entity_destruction(&mut commands, &mut destroyed_meteors, score, meteor_level, meteor_type, split, weight, velocity, blast.position.truncate(), impulse, transform) {
+				chained_blasts.push(chained_blast);
+			}
+			commands.entity(entity_meteor).despawn_recursive();
 		}
-	} else {
-		None
 	}
+
+	blast_events.p1().send_batch(chained_blasts);
 }
 
 fn handle_entity_destruction(
-	mut fragments: &mut ResMut<Fragments>,
+	commands: &mut Commands,
 	mut destroyed_meteors: &mut ResMut<DestroyedMeteors>,
+	score: Option<&mut ResMut<Score>>,
 	meteor_level: &MeteorLevel,
-	mass: &ColliderMassProperties,
-	velocity: Vec2,
+	meteor_type: &MeteorType,
+	split: &SplitRules,
+	weight: &Weight,
+	velocity: &Velocity,
+	impact: Vec2,
+	impulse: Vec2,
 	transform: &Transform
-) {
+) -> Option<MeteorBlastEvent> {
 	let entity_translation = transform.translation; 
+	let by_player = score.is_some();
 	
-	fragments.0.push(entity_translation.clone());
-
-	if meteor_level.0 < 3 {
-		destroyed_meteors.0.push((
-			MeteorDefinition {
-				weight: match mass {
-						ColliderMassProperties::Mass(value) => *value,
-						_ => panic!()
-					},
-				speed: [velocity.x, velocity.y],
-				kind: 0,
-				level: meteor_level.0
-			},
-			entity_translation.clone()
-		));
+	let radius = METEOR_SIZE.0 / 2. * transform.scale.x;
+
+	commands.spawn(ExplosionToSpawn {
+		position: entity_translation,
+		size: radius * 2.,
+		velocity: velocity.linvel,
+		color: meteor_type.0.tint(),
+	})
+	.insert(StateScoped(InGame));
+
+	if let Some(score) = score {
+		score.add(meteor_points(meteor_level.0));
 	}
+
+	if meteor_level.0 < split.max_level && split.fragments(meteor_type.0) > 0 {
+		destroyed_meteors.0.push(BrokenMeteor {
+			kind: meteor_type.0,
+			level: meteor_level.0,
+			split: *split,
+			weight: weight.0,
+			radius,
+			position: entity_translation,
+			velocity: velocity.linvel,
+			angvel: velocity.angvel,
+			impact,
+			impulse,
+		});
+	}
+
+	(meteor_type.0 == MeteorKind::Explosive).then_some(MeteorBlastEvent { position: entity_translation, by_player })
 }
\ No newline at end of file
@@ -0,0 +1,147 @@
+use std::f32::consts::{PI, TAU};
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use super::{meteor::SpawnEdge, WinSize};
+
+const PLAYER_SAFE_RADIUS: f32 = 200.;
+const METEOR_SPACING: f32 = 120.;
+const SPAWN_RING_RADIUS: f32 = 250.;
+/// Chance for a `random` entry to enter from an edge rather than appear inside the field.
+const EDGE_PREFERENCE: f64 = 0.75;
+const PLACEMENT_ATTEMPTS: usize = 30;
+const VELOCITY_ATTEMPTS: usize = 10;
+/// Half-angle of the cone around the direction of the player that a new meteor must not head into.
+const PLAYER_AVOIDANCE_ANGLE: f32 = PI / 6.;
+
+/// Picks spawn positions and initial velocities for new meteors.
+///
+/// Positions keep away from the player and from every other meteor, including the ones placed
+/// earlier through the same `SpawnPlacement` (Poisson-disc style dart throwing). When no candidate
+/// satisfies both distances, the one farthest from its nearest neighbour wins.
+pub struct SpawnPlacement {
+    half_size: Vec2,
+    player: Option<Vec2>,
+    occupied: Vec<Vec2>,
+}
+
+impl SpawnPlacement {
+    pub fn new(win_size: &WinSize, player: Option<Vec2>, meteors: impl IntoIterator<Item = Vec2>) -> Self {
+        Self {
+            half_size: Vec2::new(win_size.width / 2., win_size.height / 2.),
+            player,
+            occupied: meteors.into_iter().collect(),
+        }
+    }
+
+    /// Returns a free position matching `edge` and reserves it for the following placements.
+    pub fn place(&mut self, rng: &mut impl Rng, edge: SpawnEdge) -> Vec2 {
+        let mut best = (self.candidate(rng, edge), f32::MIN);
+
+        for _ in 0..PLACEMENT_ATTEMPTS {
+            let candidate = self.candidate(rng, edge);
+            let clearance = self.clearance(candidate);
+
+            if clearance >= 0. {
+                best = (candidate, clearance);
+                break;
+            }
+            if clearance > best.1 {
+                best = (candidate, clearance);
+            }
+        }
+
+        self.occupied.push(best.0);
+        best.0
+    }
+
+    /// Returns a velocity of a random length within `speed` that leaves the field's edges inwards
+    /// and does not point at the player.
+    pub fn velocity(&self, rng: &mut impl Rng, position: Vec2, speed: [f32; 2]) -> Vec2 {
+        let mut velocity = Vec2::ZERO;
+
+        for _ in 0..VELOCITY_ATTEMPTS {
+            velocity = self.inwards(position, Vec2::from_angle(rng.gen_range(0.0..TAU)) * rng.gen_range(speed[0]..=speed[1]));
+
+            if !self.heads_to_player(position, velocity) {
+                return velocity;
+            }
+        }
+
+        // Every attempt pointed at the player: turn the last one out of the avoidance cone.
+        let Some(to_player) = self.player.map(|player| player - position) else {
+            return velocity;
+        };
+        let side = if to_player.perp_dot(velocity) >= 0. { 1. } else { -1. };
+
+        Vec2::from_angle(to_player.to_angle() + side * PLAYER_AVOIDANCE_ANGLE * 2.) * velocity.length()
+    }
+
+    fn candidate(&self, rng: &mut impl Rng, edge: SpawnEdge) -> Vec2 {
+        let half_size = self.half_size;
+        let along_width = rng.gen_range(-half_size.x..=half_size.x);
+        let along_height = rng.gen_range(-half_size.y..=half_size.y);
+
+        match edge {
+            SpawnEdge::Random if rng.gen_bool(EDGE_PREFERENCE) => self.candidate(rng, SpawnEdge::Edges),
+            SpawnEdge::Random => Vec2::new(along_width, along_height),
+            SpawnEdge::Left => Vec2::new(-half_size.x, along_height),
+            SpawnEdge::Right => Vec2::new(half_size.x, along_height),
+            SpawnEdge::Top => Vec2::new(along_width, half_size.y),
+            SpawnEdge::Bottom => Vec2::new(along_width, -half_size.y),
+            SpawnEdge::Edges => {
+                let edge = [SpawnEdge::Left, SpawnEdge::Right, SpawnEdge::Top, SpawnEdge::Bottom][rng.gen_range(0..4)];
+                self.candidate(rng, edge)
+            },
+            SpawnEdge::Corners => {
+                let x = if rng.gen_bool(0.5) { half_size.x } else { -half_size.x };
+                let y = if rng.gen_bool(0.5) { half_size.y } else { -half_size.y };
+                Vec2::new(x, y)
+            },
+            SpawnEdge::Ring => match self.player {
+                Some(player) => self.wrap(player + Vec2::from_angle(rng.gen_range(0.0..TAU)) * SPAWN_RING_RADIUS),
+                None => self.candidate(rng, SpawnEdge::Random),
+            },
+        }
+    }
+
+    /// Smallest margin by which `position` clears the required distances, negative when too close.
+    fn clearance(&self, position: Vec2) -> f32 {
+        let player_clearance = self.player
+            .map_or(f32::MAX, |player| self.offset(position, player).length() - PLAYER_SAFE_RADIUS);
+
+        self.occupied.iter()
+            .map(|meteor| self.offset(position, *meteor).length() - METEOR_SPACING)
+            .fold(player_clearance, f32::min)
+    }
+
+    fn inwards(&self, position: Vec2, mut velocity: Vec2) -> Vec2 {
+        if position.x.abs() >= self.half_size.x {
+            velocity.x = velocity.x.abs() * -position.x.signum();
+        }
+        if position.y.abs() >= self.half_size.y {
+            velocity.y = velocity.y.abs() * -position.y.signum();
+        }
+        velocity
+    }
+
+    fn heads_to_player(&self, position: Vec2, velocity: Vec2) -> bool {
+        // Straight line on screen rather than across the wrap: that is what the player sees coming.
+        self.player.is_some_and(|player| (player - position).angle_between(velocity).abs() < PLAYER_AVOIDANCE_ANGLE)
+    }
+
+    /// Shortest offset from `from` to `to` on the wrapping field.
+    fn offset(&self, from: Vec2, to: Vec2) -> Vec2 {
+        let size = self.half_size * 2.;
+        let offset = to - from;
+
+        offset - size * (offset / size).round()
+    }
+
+    fn wrap(&self, position: Vec2) -> Vec2 {
+        let size = self.half_size * 2.;
+
+        position - size * (position / size).round()
+    }
+}
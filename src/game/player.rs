@@ -1,43 +1,123 @@
-use std::{f32::consts::PI, time::Instant};
-use  bevy::{prelude::*, sprite::MaterialMesh2dBundle};
-use bevy_rapier2d::{na::Translation, prelude::{ActiveEvents, Collider, CollisionGroups, Group, KinematicCharacterController, RigidBody, Sensor, Velocity}};
-use rand::{random, Rng};
-use super::{components::{Acceleration, Direction, Laser, LifeTime, Player, RocketDragTimer, RocketFire}, GameTextures, WinSize, BASE_SPEED, LASER_SIZE, PLAYER_SIZE, SPRITE_SCALE, TIME_STEP };
+use std::f32::consts::PI;
+use  bevy::{prelude::*, sprite::MaterialMesh2dBundle, time::Stopwatch};
+use bevy_rapier2d::{na::Translation, prelude::{ActiveEvents, Collider, ColliderDisabled, ColliderMassProperties, Damping, ExternalForce, LockedAxes, Restitution, RigidBody, Sensor, Sleeping, Velocity}};
+use rand::Rng;
+use super::{controls::ShipInput, state::{field_active, GameState, InGame}, rng::{GameRng, RngStream}, collision::{collision_dispatch_system, CollisionLayer, EnemyHitPlayerEvent, LaserHitPlayerEvent, MeteorHitPlayerEvent}, meteor::{MeteorBlastEvent, BLAST_DAMAGE, BLAST_RADIUS}, weapon::{spawn_laser, LaserSource, LASER_SPEED}, components::{Damage, Direction, ExplosionToSpawn, Health, Hyperspace, HyperspaceCooldown, HyperspacePhase, Invulnerability, LifeTime, Meteor, Player, RocketDragTimer, RocketFire, ScreenWrap, Thrust}, GameTextures, WinSize, BASE_SPEED, COLLISION_DAMAGE, LASER_SIZE, PLAYER_SIZE, SPRITE_SCALE };
 
 
 // region:    --- Constants
 
 const LASER_COOLDOWN: f32 = 0.25;
+const LASER_DAMAGE: f32 = 1.;
+
+const PLAYER_HEALTH: f32 = 1.;
+const PLAYER_MASS: f32 = 10.;
+const PLAYER_RESTITUTION: f32 = 0.5;
+
+const PLAYER_LIVES: u32 = 3;
+const RESPAWN_DELAY: f32 = 2.;
+const INVULNERABILITY_DURATION: f32 = 3.;
+const BLINK_INTERVAL: f32 = 0.1;
+
+const HYPERSPACE_PLACEMENT_ATTEMPTS: usize = 20;
 // endregion: --- Constants
 
 // region:    --- Resources
 #[derive(Resource)]
 pub struct TimeSinceLastShot {
-    pub time: Instant,
+    pub time: Stopwatch,
+}
+
+#[derive(Resource)]
+pub struct Lives(pub u32);
+
+#[derive(Resource)]
+pub struct RespawnTimer(pub Timer);
+
+/// Flight model of the ship: it keeps drifting until drag or thrust change its velocity.
+#[derive(Resource)]
+pub struct FlightSettings {
+    /// Acceleration given by the engine along the heading, in pixels per second squared.
+    pub thrust: f32,
+    /// Linear damping of the ship's body, 0 for none.
+    pub drag: f32,
+    /// Cap on the length of the ship's velocity, in pixels per second.
+    pub max_speed: f32,
+}
+
+impl Default for FlightSettings {
+    fn default() -> Self {
+        Self { thrust: 400., drag: 0.4, max_speed: 350. }
+    }
+}
+
+#[derive(Resource)]
+pub struct HyperspaceSettings {
+    pub cooldown: f32,
+    /// Probability, between 0 and 1, that the ship explodes when it comes out of hyperspace.
+    pub self_destruct_chance: f32,
+    /// When set, the ship never lands closer than this distance to a meteor (if such a spot can be found).
+    pub safe_radius: Option<f32>,
+}
+
+impl Default for HyperspaceSettings {
+    fn default() -> Self {
+        Self { cooldown: 1.5, self_destruct_chance: 0.1, safe_radius: Some(150.) }
+    }
 }
 // endregion: --- Resources
 
+// region:    --- Events
+#[derive(Event)]
+pub struct GameOverEvent;
+// endregion: --- Events
+
 pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app
-            .add_systems(PostStartup, player_spawn_system)
-            .add_systems(Update,
+            .insert_resource(Lives(PLAYER_LIVES))
+            .init_resource::<HyperspaceSettings>()
+            .init_resource::<FlightSettings>()
+            .add_event::<GameOverEvent>()
+            .add_systems(OnEnter(InGame), (reset_lives_system, player_spawn_system))
+            .add_systems(OnExit(InGame), clear_respawn_timer_system)
+            .add_systems(FixedUpdate, (
+                (player_collision_system, player_blast_system).chain().after(collision_dispatch_system),
+                player_respawn_system.run_if(resource_exists::<RespawnTimer>),
+                invulnerability_system,
+                player_hyperspace_event_system,
+                hyperspace_system,
+                game_over_system,
+            ).run_if(field_active))
+            .add_systems(FixedUpdate,
         (
                     player_rotation_event_system,
-                    player_acceleration_event_system,
-                    move_player_system,
+                    player_thrust_event_system,
+                    player_thrust_system,
                     propulsion_effect_system,
                     edit_rocket_drag_system,
                     player_shooting_system,
                     rotate_player_system,
-                ).chain()
+                ).chain().run_if(field_active)
             );
     }
 }
 
-fn player_spawn_system(mut commands: Commands, game_textures: Res<GameTextures>) {
+fn reset_lives_system(mut lives: ResMut<Lives>) {
+    lives.0 = PLAYER_LIVES;
+}
+
+fn clear_respawn_timer_system(mut commands: Commands) {
+    commands.remove_resource::<RespawnTimer>();
+}
+
+fn player_spawn_system(mut commands: Commands, game_textures: Res<GameTextures>, flight_settings: Res<FlightSettings>) {
+    spawn_player(&mut commands, &game_textures, &flight_settings);
+}
+
+fn spawn_player(commands: &mut Commands, game_textures: &Res<GameTextures>, flight_settings: &FlightSettings) -> Entity {
 	commands
         .spawn(SpriteBundle {
             texture: game_textures.player.clone(),
@@ -51,53 +131,309 @@ fn player_spawn_system(mut commands: Commands, game_textures: Res<GameTextures>)
             },
             ..Default::default()
         })
-        .insert(KinematicCharacterController::default())
         .insert(Player)
-        .insert(Acceleration::default())
+        .insert(ScreenWrap)
+        .insert(StateScoped(InGame))
+        .insert(Thrust::default())
+        .insert(RigidBody::Dynamic)
         .insert(Collider::cuboid(PLAYER_SIZE.0 / 2., PLAYER_SIZE.1 / 2.))
-        .insert(Direction::default());
+        .insert(ColliderMassProperties::Mass(PLAYER_MASS))
+        .insert(Restitution::coefficient(PLAYER_RESTITUTION))
+        .insert(CollisionLayer::Player.collision_groups())
+        .insert(ActiveEvents::COLLISION_EVENTS)
+        // The heading only follows `Direction`, bumps must not spin the ship.
+        .insert(LockedAxes::ROTATION_LOCKED)
+        .insert(Damping { linear_damping: flight_settings.drag, angular_damping: 0. })
+        .insert(ExternalForce::default())
+        .insert(Sleeping::disabled())
+        .insert(Direction::default())
+        .insert(Velocity::zero())
+        .insert(Health::new(PLAYER_HEALTH))
+        .insert(Damage(COLLISION_DAMAGE))
+        .insert(HyperspaceCooldown::default())
+        .id()
 }
 
-fn player_rotation_event_system(kb: Res<ButtonInput<KeyCode>>, mut query: Query<(&mut Acceleration, &mut Direction), With<Player>>) {
-    if let Ok((mut acceleration, mut rotation)) = query.get_single_mut() {
-        if kb.pressed(KeyCode::ArrowLeft) {
-            rotation.rotate(0.5);
-            acceleration.stop();
-        } else if kb.pressed(KeyCode::ArrowRight) {
-            rotation.rotate(-0.5);
-            acceleration.stop();
+fn destroy_player(
+    commands: &mut Commands,
+    lives: &mut ResMut<Lives>,
+    game_over_events: &mut EventWriter<GameOverEvent>,
+    entity_player: Entity,
+    translation: Vec3,
+    velocity: Vec2
+) {
+    commands.spawn(ExplosionToSpawn {
+        position: translation,
+        size: PLAYER_SIZE.0 * SPRITE_SCALE,
+        velocity,
+        color: Color::WHITE,
+    })
+    .insert(StateScoped(InGame));
+    commands.entity(entity_player).despawn();
+
+    lives.0 = lives.0.saturating_sub(1);
+    if lives.0 == 0 {
+        game_over_events.send(GameOverEvent);
+    } else {
+        commands.insert_resource(RespawnTimer(Timer::from_seconds(RESPAWN_DELAY, TimerMode::Once)));
+    }
+}
+
+fn player_collision_system(
+    mut commands: Commands,
+    mut lives: ResMut<Lives>,
+    mut laser_hit_player_events: EventReader<LaserHitPlayerEvent>,
+    mut meteor_hit_player_events: EventReader<MeteorHitPlayerEvent>,
+    mut enemy_hit_player_events: EventReader<EnemyHitPlayerEvent>,
+    mut game_over_events: EventWriter<GameOverEvent>,
+    mut query_player: Query<(&Transform, &Velocity, &mut Health), (With<Player>, Without<Invulnerability>, Without<Hyperspace>)>,
+    query_damage: Query<&Damage>
+) {
+    let laser_hits = laser_hit_player_events.read().map(|hit| (hit.player, hit.laser, true));
+    let meteor_hits = meteor_hit_player_events.read().map(|hit| (hit.player, hit.meteor, false));
+    let enemy_hits = enemy_hit_player_events.read().map(|hit| (hit.player, hit.enemy, false));
+
+    for (entity_player, other, is_laser) in laser_hits.chain(meteor_hits).chain(enemy_hits) {
+        let Ok((transform, velocity, mut health)) = query_player.get_mut(entity_player) else {
+            continue;
+        };
+        let Ok(damage) = query_damage.get(other) else {
+            continue;
+        };
+
+        if is_laser {
+            commands.entity(other).despawn();
         }
-    }    
+
+        if health.take(damage) {
+            destroy_player(&mut commands, &mut lives, &mut game_over_events, entity_player, transform.translation, velocity.linvel);
+            break;
+        }
+    }
 }
 
-fn player_acceleration_event_system(kb: Res<ButtonInput<KeyCode>>, mut query: Query<(&Transform, &mut Acceleration, &Direction), With<Player>>) {
-    if let Ok((transform, mut acceleration, direction)) = query.get_single_mut() {
-        if kb.pressed(KeyCode::ArrowUp) {
-            acceleration.accelerate();
-            acceleration.calculate_translation(&direction.rotation_angle_degrees);
-        } else {
-            acceleration.stop();
+fn player_blast_system(
+    mut commands: Commands,
+    mut lives: ResMut<Lives>,
+    mut blast_events: EventReader<MeteorBlastEvent>,
+    mut game_over_events: EventWriter<GameOverEvent>,
+    mut query_player: Query<(Entity, &Transform, &Velocity, &mut Health), (With<Player>, Without<Invulnerability>, Without<Hyperspace>)>
+) {
+    let Ok((entity_player, transform, velocity, mut health)) = query_player.get_single_mut() else {
+        blast_events.clear();
+        return;
+    };
+
+    for blast in blast_events.read() {
+        if blast.position.truncate().distance(transform.translation.truncate()) <= BLAST_RADIUS && health.take(&BLAST_DAMAGE) {
+            destroy_player(&mut commands, &mut lives, &mut game_over_events, entity_player, transform.translation, velocity.linvel);
+        }
+    }
+}
+
+fn player_respawn_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    game_textures: Res<GameTextures>,
+    flight_settings: Res<FlightSettings>,
+    mut respawn_timer: ResMut<RespawnTimer>
+) {
+    respawn_timer.0.tick(time.delta());
+    if !respawn_timer.0.just_finished() {
+        return;
+    }
+
+    let entity_player = spawn_player(&mut commands, &game_textures, &flight_settings);
+    commands.entity(entity_player).insert(Invulnerability {
+        timer: Timer::from_seconds(INVULNERABILITY_DURATION, TimerMode::Once),
+        blink_timer: Timer::from_seconds(BLINK_INTERVAL, TimerMode::Repeating),
+    });
+    commands.remove_resource::<RespawnTimer>();
+}
+
+fn invulnerability_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Invulnerability, &mut Visibility), With<Player>>
+) {
+    for (entity, mut invulnerability, mut visibility) in query.iter_mut() {
+        invulnerability.timer.tick(time.delta());
+
+        if invulnerability.timer.finished() {
+            *visibility = Visibility::Inherited;
+            commands.entity(entity).remove::<Invulnerability>();
+            continue;
+        }
+
+        if invulnerability.blink_timer.tick(time.delta()).just_finished() {
+            *visibility = match *visibility {
+                Visibility::Hidden => Visibility::Inherited,
+                _ => Visibility::Hidden,
+            };
+        }
+    }
+}
+
+fn player_hyperspace_event_system(
+    mut commands: Commands,
+    input: Res<ShipInput>,
+    settings: Res<HyperspaceSettings>,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Thrust, &mut Velocity, &mut HyperspaceCooldown), (With<Player>, Without<Hyperspace>)>
+) {
+    if let Ok((entity, mut thrust, mut velocity, mut cooldown)) = query.get_single_mut() {
+        cooldown.0.tick(time.delta());
+
+        if !input.hyperspace || !cooldown.0.finished() {
+            return;
+        }
+
+        // The ship comes out of hyperspace at rest.
+        thrust.firing = false;
+        velocity.linvel = Vec2::ZERO;
+        cooldown.0 = Timer::from_seconds(settings.cooldown, TimerMode::Once);
+
+        commands.entity(entity)
+            .insert(Hyperspace::default())
+            .insert(ColliderDisabled);
+    }
+}
+
+fn hyperspace_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    win_size: Res<WinSize>,
+    settings: Res<HyperspaceSettings>,
+    mut lives: ResMut<Lives>,
+    mut game_rng: ResMut<GameRng>,
+    mut game_over_events: EventWriter<GameOverEvent>,
+    mut query: Query<(Entity, &mut Transform, &mut Sprite, &mut Hyperspace), With<Player>>,
+    query_meteor: Query<&Transform, (With<Meteor>, Without<Player>)>
+) {
+    let Ok((entity, mut transform, mut sprite, mut hyperspace)) = query.get_single_mut() else {
+        return;
+    };
+
+    hyperspace.timer.tick(time.delta());
+    let fraction = hyperspace.timer.fraction();
+
+    match hyperspace.phase {
+        HyperspacePhase::FadeOut => {
+            sprite.color.set_alpha(1. - fraction);
+
+            if hyperspace.timer.just_finished() {
+                let rng = game_rng.stream(RngStream::Hyperspace);
+                if rng.gen::<f32>() < settings.self_destruct_chance {
+                    destroy_player(&mut commands, &mut lives, &mut game_over_events, entity, transform.translation, Vec2::ZERO);
+                    return;
+                }
+
+                let meteors: Vec<Vec2> = query_meteor.iter().map(|transform| transform.translation.truncate()).collect();
+                let destination = get_hyperspace_destination(rng, &win_size, &meteors, settings.safe_radius);
+                transform.translation.x = destination.x;
+                transform.translation.y = destination.y;
+
+                hyperspace.phase = HyperspacePhase::FadeIn;
+                hyperspace.timer.reset();
+            }
+        },
+        HyperspacePhase::FadeIn => {
+            sprite.color.set_alpha(fraction);
+
+            if hyperspace.timer.just_finished() {
+                commands.entity(entity)
+                    .remove::<Hyperspace>()
+                    .remove::<ColliderDisabled>();
+            }
+        }
+    }
+}
+
+fn get_hyperspace_destination(rng: &mut impl Rng, win_size: &WinSize, meteors: &[Vec2], safe_radius: Option<f32>) -> Vec2 {
+    let mut random_position = || Vec2 {
+        x: win_size.width / 2. * rng.gen_range(-1.0..=1.0),
+        y: win_size.height / 2. * rng.gen_range(-1.0..=1.0),
+    };
+
+    let Some(safe_radius) = safe_radius else {
+        return random_position();
+    };
+
+    let distance_to_nearest_meteor = |position: Vec2| -> f32 {
+        meteors.iter()
+            .map(|meteor| meteor.distance(position))
+            .fold(f32::MAX, f32::min)
+    };
+
+    // Keep the safest candidate in case every attempt lands too close to a meteor.
+    let mut best_position = random_position();
+    let mut best_distance = distance_to_nearest_meteor(best_position);
+
+    for _ in 0..HYPERSPACE_PLACEMENT_ATTEMPTS {
+        if best_distance >= safe_radius {
+            break;
+        }
+
+        let position = random_position();
+        let distance = distance_to_nearest_meteor(position);
+        if distance > best_distance {
+            best_position = position;
+            best_distance = distance;
+        }
+    }
+
+    best_position
+}
+
+fn game_over_system(mut game_over_events: EventReader<GameOverEvent>, mut next_state: ResMut<NextState<GameState>>) {
+    for _ in game_over_events.read() {
+        info!("Game over");
+        next_state.set(GameState::GameOver);
+    }
+}
+
+fn player_rotation_event_system(input: Res<ShipInput>, time: Res<Time>, mut query: Query<&mut Direction, (With<Player>, Without<Hyperspace>)>) {
+    if let Ok(mut rotation) = query.get_single_mut() {
+        if input.rotate_left {
+            rotation.rotate(0.5, time.delta_seconds());
+        } else if input.rotate_right {
+            rotation.rotate(-0.5, time.delta_seconds());
         }
     }    
 }
 
-fn move_player_system(mut query: Query<(&mut KinematicCharacterController, &Acceleration), With<Player>>) {
-    if let Ok((mut controller, acceleration)) = query.get_single_mut() {
-        let mut translation = &mut controller.translation.unwrap_or_default();
-        translation.x += acceleration.x * TIME_STEP * BASE_SPEED;
-        translation.y += acceleration.y * TIME_STEP * BASE_SPEED;
-        controller.translation = Some(*translation);
+fn player_thrust_event_system(input: Res<ShipInput>, mut query: Query<&mut Thrust, (With<Player>, Without<Hyperspace>)>) {
+    if let Ok(mut thrust) = query.get_single_mut() {
+        thrust.firing = input.thrust;
+    }    
+}
+
+/// Pushes the ship along its heading while the engine fires; turning leaves its velocity alone.
+fn player_thrust_system(
+    flight_settings: Res<FlightSettings>,
+    mut query: Query<(&Thrust, &Direction, &mut ExternalForce, &mut Velocity, &mut Damping), With<Player>>
+) {
+    if let Ok((thrust, direction, mut external_force, mut velocity, mut damping)) = query.get_single_mut() {
+        external_force.force = if thrust.firing {
+            calculate_translation(Vec2::ZERO, direction.rotation_angle_degrees.to_radians(), flight_settings.thrust * PLAYER_MASS)
+        } else {
+            Vec2::ZERO
+        };
+
+        damping.linear_damping = flight_settings.drag;
+        velocity.linvel = velocity.linvel.clamp_length_max(flight_settings.max_speed);
     }
 }
 fn propulsion_effect_system(
     mut commands: Commands,
     game_textures: Res<GameTextures>,
-    query: Query<(&Transform, &Acceleration, &Direction), With<Player>>, // normalement accelation ne sera plus utile, un run_if vérifiera qu'il y a eu ou non acceleration
+    mut game_rng: ResMut<GameRng>,
+    query: Query<(&Transform, &Thrust, &Direction), With<Player>>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
 ) {
-    if let Ok((transform, acceleration, direction)) = query.get_single() {
-        if acceleration.acceleration == 0. {
+    if let Ok((transform, thrust, direction)) = query.get_single() {
+        if !thrust.firing {
             return;
         }
         let y_offset = PLAYER_SIZE.0 / 2. * SPRITE_SCALE * -1. + 10.;
@@ -122,14 +458,16 @@ fn propulsion_effect_system(
                 ..Default::default()
             })
             .insert(RocketFire)
+            .insert(StateScoped(InGame))
             .insert(LifeTime(Timer::from_seconds(0.05, TimerMode::Once)));
         
         
-        if rand::thread_rng().gen::<f32>() > 0.75 {
-            let rocket_drag_timer = RocketDragTimer::new(0.25);
+        let rng = game_rng.stream(RngStream::Cosmetic);
+        if rng.gen::<f32>() > 0.75 {
+            let rocket_drag_timer = RocketDragTimer::new(0.25, rng);
             let life_time_in_seconds_for_rocket_drag = rocket_drag_timer.2.duration().as_secs_f32();
-            let random_angle = rand::thread_rng().gen_range(-25..=25) as f32;
-            let random_angvel = rand::thread_rng().gen_range((0.)..PI);
+            let random_angle = rng.gen_range(-25..=25) as f32;
+            let random_angvel = rng.gen_range((0.)..PI);
             
             commands
                 .spawn(MaterialMesh2dBundle {
@@ -143,8 +481,9 @@ fn propulsion_effect_system(
                     ..default()
                 })
                 .insert(RigidBody::KinematicVelocityBased)
-                .insert(Velocity { linvel: calculate_velocity(Vec2::ZERO, (direction.rotation_angle_degrees + 180. + random_angle).to_radians(), 100.), angvel: random_angvel })
+                .insert(Velocity { linvel: calculate_translation(Vec2::ZERO, (direction.rotation_angle_degrees + 180. + random_angle).to_radians(), 100.), angvel: random_angvel })
                 .insert(rocket_drag_timer)
+                .insert(StateScoped(InGame))
                 .insert(LifeTime(Timer::from_seconds(life_time_in_seconds_for_rocket_drag, TimerMode::Once)));
         }
     }
@@ -152,20 +491,25 @@ fn propulsion_effect_system(
 
 fn player_shooting_system(
     mut commands: Commands,
-    time_since_last_shot: Option<ResMut<TimeSinceLastShot>>,
+    time: Res<Time>,
+    mut time_since_last_shot: Option<ResMut<TimeSinceLastShot>>,
     game_textures: Res<GameTextures>,
-    kb: Res<ButtonInput<KeyCode>>,
-    query: Query<(&Transform, &Acceleration, &Direction), With<Player>>
+    input: Res<ShipInput>,
+    query: Query<(&Transform, &Velocity, &Direction), (With<Player>, Without<Hyperspace>)>
 ) {
-    if let Ok((transform, acceleration, direction)) = query.get_single() {
-        if kb.just_pressed(KeyCode::Space) {
+    if let Some(time_since_last_shot) = time_since_last_shot.as_mut() {
+        time_since_last_shot.time.tick(time.delta());
+    }
+
+    if let Ok((transform, velocity, direction)) = query.get_single() {
+        if input.fire {
             if let Some(mut time_since_last_shot) = time_since_last_shot {
-                if time_since_last_shot.time.elapsed().as_secs_f32() < LASER_COOLDOWN {
+                if time_since_last_shot.time.elapsed_secs() < LASER_COOLDOWN {
                     return;
                 }
-                time_since_last_shot.time = Instant::now();
+                time_since_last_shot.time.reset();
             } else {
-                commands.insert_resource(TimeSinceLastShot { time: Instant::now() });
+                commands.insert_resource(TimeSinceLastShot { time: Stopwatch::new() });
             }
 
             let y_offset = PLAYER_SIZE.0 / 2. * SPRITE_SCALE;
@@ -174,26 +518,17 @@ fn player_shooting_system(
                 direction.rotation_angle_degrees.to_radians(),
                 y_offset
             ).extend(0.);
-            
-            commands
-                .spawn(SpriteBundle {
-                    texture: game_textures.laser.clone(),
-                    sprite: Sprite {
-                        ..Default::default()
-                    },
-                    transform: Transform {
-                        translation: laser_translation,
-                        scale: Vec3::new(SPRITE_SCALE, SPRITE_SCALE, 1.),
-                        rotation: Quat::from_rotation_z(direction.rotation_angle_degrees.to_radians())
-                    },
-                    ..Default::default()
-                })
-                .insert(Laser)
-                .insert(RigidBody::KinematicVelocityBased)
-                .insert(Collider::capsule(Vec2 { x: 0., y: 0. }, Vec2 { x: 0., y: LASER_SIZE.1 / 2. }, LASER_SIZE.0 / 2.))
-                .insert(ActiveEvents::COLLISION_EVENTS)
-                .insert(Velocity::linear(calculate_velocity(Vec2::new(acceleration.x, acceleration.y), direction.rotation_angle_degrees.to_radians(), 500.)))
-                .insert(LifeTime(Timer::from_seconds(1., TimerMode::Once)));
+
+            spawn_laser(
+                &mut commands,
+                &game_textures,
+                laser_translation,
+                direction.rotation_angle_degrees.to_radians(),
+                // Shots carry the ship's momentum.
+                calculate_translation(Vec2::ZERO, direction.rotation_angle_degrees.to_radians(), LASER_SPEED) + velocity.linvel,
+                LASER_DAMAGE,
+                LaserSource::Player
+            );
         }
     }
 }
@@ -205,21 +540,6 @@ fn calculate_translation(mut vec2: Vec2, angle_radians: f32, shift: f32) -> Vec2
     vec2
 }
 
-fn calculate_velocity(vec2: Vec2, angle_radians: f32, boost: f32) -> Vec2 {
-    let Vec2 { mut x, mut y } = calculate_translation(Vec2::ZERO, angle_radians, boost);
-
-    let extra_speed = |m: &mut f32, n: f32| {
-        if (*m < 0. && n < 0.) || (*m > 0. && n > 0.) {
-            *m += n * TIME_STEP * BASE_SPEED;
-        }
-    };
-
-    extra_speed(&mut x, vec2.x);
-    extra_speed(&mut y, vec2.y);
-    
-    Vec2 { x, y }
-}
-
 fn rotate_player_system(mut query: Query<(&mut Transform, &Direction), With<Player>>) {
 	if let Ok((mut transform, rotation)) = query.get_single_mut() {
 		transform.rotation = Quat::from_rotation_z(rotation.rotation_angle_degrees.to_radians());
@@ -0,0 +1,366 @@
+use std::{fmt, path::{Path, PathBuf}};
+
+use bevy::{app::AppExit, prelude::*};
+
+use super::{controls::{sample_ship_input_system, ShipInput}, rng::{reseed_system, GameRng}, state::{field_active, fixed_state_transition_system, GameState, InGame}, wave_file::WaveFile, GameMode};
+
+// region:    --- Constants
+
+const REPLAY_MAGIC: &[u8; 4] = b"ASTR";
+const REPLAY_VERSION: u8 = 1;
+// Header layout, each field right after the previous one.
+const VERSION_OFFSET: usize = REPLAY_MAGIC.len();
+const SEED_OFFSET: usize = VERSION_OFFSET + 1;
+const WAVE_FILE_HASH_OFFSET: usize = SEED_OFFSET + 8;
+const TICK_RATE_OFFSET: usize = WAVE_FILE_HASH_OFFSET + 8;
+const MODE_OFFSET: usize = TICK_RATE_OFFSET + 8;
+const HEADER_LEN: usize = MODE_OFFSET + 1;
+/// A control byte and its `u16` repeat count.
+const RUN_LEN: usize = 3;
+const PLAYBACK_SPEEDS: [f32; 4] = [1., 2., 4., 8.];
+// endregion: --- Constants
+
+// region:    --- Replay File
+/// Everything needed to play a game again: the seed and settings it started from, then the ship's
+/// controls for every tick.
+///
+/// Stored as a fixed header followed by runs of identical ticks, each a control byte and a
+/// little-endian `u16` repeat count.
+#[derive(Clone, Debug)]
+pub struct Replay {
+    pub seed: u64,
+    pub wave_file_hash: u64,
+    pub tick_rate: f64,
+    pub mode: GameMode,
+    pub inputs: Vec<ShipInput>,
+}
+
+impl Replay {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ReplayError> {
+        let bytes = std::fs::read(path).map_err(ReplayError::Io)?;
+
+        Self::from_bytes(&bytes)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.to_bytes())
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_LEN);
+        bytes.extend_from_slice(REPLAY_MAGIC);
+        bytes.push(REPLAY_VERSION);
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        bytes.extend_from_slice(&self.wave_file_hash.to_le_bytes());
+        bytes.extend_from_slice(&self.tick_rate.to_le_bytes());
+        bytes.push(match self.mode {
+            GameMode::Classic => 0,
+            GameMode::Endless => 1,
+        });
+        debug_assert_eq!(bytes.len(), HEADER_LEN);
+
+        let mut inputs = self.inputs.iter().map(ShipInput::to_bits).peekable();
+        while let Some(bits) = inputs.next() {
+            let mut run: u16 = 1;
+            while run < u16::MAX && inputs.next_if_eq(&bits).is_some() {
+                run += 1;
+            }
+            bytes.push(bits);
+            bytes.extend_from_slice(&run.to_le_bytes());
+        }
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ReplayError> {
+        if !bytes.starts_with(REPLAY_MAGIC) {
+            return Err(ReplayError::NotAReplay);
+        }
+        if bytes.len() < HEADER_LEN {
+            return Err(ReplayError::Truncated);
+        }
+        if bytes[VERSION_OFFSET] != REPLAY_VERSION {
+            return Err(ReplayError::UnsupportedVersion(bytes[VERSION_OFFSET]));
+        }
+
+        let read_u64 = |offset: usize| u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap());
+        let mode = match bytes[MODE_OFFSET] {
+            0 => GameMode::Classic,
+            1 => GameMode::Endless,
+            mode => return Err(ReplayError::UnknownMode(mode)),
+        };
+
+        let runs = bytes[HEADER_LEN..].chunks(RUN_LEN);
+        if runs.clone().any(|run| run.len() < RUN_LEN) {
+            return Err(ReplayError::Truncated);
+        }
+
+        let inputs = runs
+            .flat_map(|run| std::iter::repeat(ShipInput::from_bits(run[0])).take(u16::from_le_bytes([run[1], run[2]]) as usize))
+            .collect();
+
+        Ok(Self {
+            seed: read_u64(SEED_OFFSET),
+            wave_file_hash: read_u64(WAVE_FILE_HASH_OFFSET),
+            tick_rate: f64::from_bits(read_u64(TICK_RATE_OFFSET)),
+            mode,
+            inputs,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(std::io::Error),
+    NotAReplay,
+    UnsupportedVersion(u8),
+    UnknownMode(u8),
+    Truncated,
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Io(error) => write!(f, "cannot read replay: {error}"),
+            ReplayError::NotAReplay => write!(f, "not a replay file"),
+            ReplayError::UnsupportedVersion(version) => write!(f, "unsupported replay version {version}"),
+            ReplayError::UnknownMode(mode) => write!(f, "unknown game mode {mode} in replay"),
+            ReplayError::Truncated => write!(f, "replay file is truncated"),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+// endregion: --- Replay File
+
+// region:    --- Resources
+/// Game being recorded, saved when it ends or when the app closes. `games` counts the games
+/// recorded so far, each after the first going to its own numbered file next to `path`.
+#[derive(Resource)]
+struct ReplayRecording {
+    path: PathBuf,
+    tick_rate: f64,
+    games: u32,
+    replay: Option<Replay>,
+}
+
+impl ReplayRecording {
+    /// `path` for the first game, then `name-2.ext`, `name-3.ext`... for the following ones.
+    fn get_game_path(&self) -> PathBuf {
+        if self.games <= 1 {
+            return self.path.clone();
+        }
+
+        let stem = self.path.file_stem().map_or_else(Default::default, |stem| stem.to_string_lossy());
+        let name = match self.path.extension() {
+            Some(extension) => format!("{stem}-{}.{}", self.games, extension.to_string_lossy()),
+            None => format!("{stem}-{}", self.games),
+        };
+        self.path.with_file_name(name)
+    }
+}
+
+/// Replay fed to the ship in place of the keyboard, until the game it recorded is over.
+/// `ticks` counts the inputs already played, `speed` indexes `PLAYBACK_SPEEDS`.
+#[derive(Resource)]
+pub struct ReplayPlayback {
+    replay: Replay,
+    ticks: usize,
+    speed: usize,
+}
+
+/// Set while a single tick is played out of a paused replay.
+#[derive(Resource)]
+struct ReplayStep;
+// endregion: --- Resources
+
+/// Records the games to `record`, or plays back `replay` from the main menu.
+/// A file already at one of the recording paths is replaced.
+///
+/// While playing back, F cycles the playback speed and N advances a paused replay by one tick.
+pub struct ReplayPlugin {
+    pub tick_rate: f64,
+    pub record: Option<PathBuf>,
+    pub replay: Option<Replay>,
+}
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        if let Some(path) = &self.record {
+            app
+                .insert_resource(ReplayRecording { path: path.clone(), tick_rate: self.tick_rate, games: 0, replay: None })
+                .add_systems(OnEnter(InGame), start_recording_system.after(reseed_system))
+                .add_systems(FixedPreUpdate, record_input_system.after(sample_ship_input_system).after(playback_input_system).run_if(field_active))
+                .add_systems(OnEnter(GameState::GameOver), save_recording_system)
+                .add_systems(Last, save_recording_system.run_if(on_event::<AppExit>()));
+        }
+
+        if let Some(replay) = &self.replay {
+            app
+                .insert_resource(ReplayPlayback { replay: replay.clone(), ticks: 0, speed: 0 })
+                .add_systems(Update, (
+                    start_playback_system.run_if(in_state(GameState::MainMenu).and_then(resource_exists::<WaveFile>)),
+                    playback_controls_system.run_if(in_state(GameState::Playing).or_else(in_state(GameState::Paused))),
+                ).run_if(resource_exists::<ReplayPlayback>))
+                .add_systems(FixedPreUpdate, playback_input_system.after(sample_ship_input_system).run_if(field_active.and_then(resource_exists::<ReplayPlayback>)))
+                .add_systems(OnExit(GameState::GameOver), stop_playback_system)
+                .add_systems(FixedLast, end_replay_step_system.before(fixed_state_transition_system).run_if(resource_exists::<ReplayStep>));
+        }
+    }
+}
+
+// region:    --- Recording
+fn start_recording_system(
+    mut recording: ResMut<ReplayRecording>,
+    game_rng: Res<GameRng>,
+    game_mode: Res<GameMode>,
+    wave_file: Res<WaveFile>
+) {
+    recording.games += 1;
+    recording.replay = Some(Replay {
+        seed: game_rng.seed(),
+        wave_file_hash: wave_file.hash,
+        tick_rate: recording.tick_rate,
+        mode: *game_mode,
+        inputs: Vec::new(),
+    });
+}
+
+fn record_input_system(input: Res<ShipInput>, mut recording: ResMut<ReplayRecording>) {
+    if let Some(replay) = recording.replay.as_mut() {
+        replay.inputs.push(*input);
+    }
+}
+
+/// Writes the game being recorded, at game over or when the app is closed mid-game.
+fn save_recording_system(mut recording: ResMut<ReplayRecording>) {
+    let Some(replay) = recording.replay.take() else {
+        return;
+    };
+
+    let path = recording.get_game_path();
+    match replay.save(&path) {
+        Ok(()) => info!("Replay of {} ticks saved to {}", replay.inputs.len(), path.display()),
+        Err(error) => warn!("Cannot save replay to {}: {error}", path.display()),
+    }
+}
+// endregion: --- Recording
+
+// region:    --- Playback
+/// Starts the recorded game once the wave file is loaded, in the mode it was recorded in.
+fn start_playback_system(
+    playback: Res<ReplayPlayback>,
+    wave_file: Res<WaveFile>,
+    mut game_mode: ResMut<GameMode>,
+    mut next_state: ResMut<NextState<GameState>>
+) {
+    if wave_file.hash != playback.replay.wave_file_hash {
+        warn!("The wave file differs from the one this replay was recorded with, the game will not play out the same");
+    }
+
+    info!("Playing back {} ticks, F changes the speed, N steps while paused", playback.replay.inputs.len());
+    *game_mode = playback.replay.mode;
+    next_state.set(GameState::Playing);
+}
+
+/// Replaces the keyboard controls sampled for this tick with the recorded ones.
+fn playback_input_system(mut playback: ResMut<ReplayPlayback>, mut input: ResMut<ShipInput>) {
+    *input = playback.replay.inputs.get(playback.ticks).copied().unwrap_or_default();
+    playback.ticks += 1;
+
+    if playback.ticks == playback.replay.inputs.len() {
+        info!("End of replay");
+    }
+}
+
+fn playback_controls_system(
+    mut commands: Commands,
+    kb: Res<ButtonInput<KeyCode>>,
+    state: Res<State<GameState>>,
+    mut playback: ResMut<ReplayPlayback>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    mut next_state: ResMut<NextState<GameState>>
+) {
+    if kb.just_pressed(KeyCode::KeyF) {
+        playback.speed = (playback.speed + 1) % PLAYBACK_SPEEDS.len();
+        virtual_time.set_relative_speed(PLAYBACK_SPEEDS[playback.speed]);
+        info!("Playback speed x{}", PLAYBACK_SPEEDS[playback.speed]);
+    }
+
+    if kb.just_pressed(KeyCode::KeyN) && *state.get() == GameState::Paused {
+        commands.insert_resource(ReplayStep);
+        next_state.set(GameState::Playing);
+    }
+}
+
+/// Hands the ship back to the keyboard once the replayed game is over.
+fn stop_playback_system(mut commands: Commands, mut virtual_time: ResMut<Time<Virtual>>) {
+    commands.remove_resource::<ReplayPlayback>();
+    virtual_time.set_relative_speed(1.);
+}
+
+/// Pauses again right after the stepped tick, before the next one of the same frame can run.
+fn end_replay_step_system(mut commands: Commands, mut next_state: ResMut<NextState<GameState>>) {
+    commands.remove_resource::<ReplayStep>();
+    next_state.set(GameState::Paused);
+}
+// endregion: --- Playback
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replay(inputs: Vec<ShipInput>) -> Replay {
+        Replay { seed: 0xDEAD_BEEF, wave_file_hash: 42, tick_rate: 60., mode: GameMode::Endless, inputs }
+    }
+
+    #[test]
+    fn round_trips_header_and_inputs() {
+        let thrust = ShipInput { thrust: true, ..default() };
+        let fire = ShipInput { fire: true, rotate_left: true, ..default() };
+        let original = replay(vec![thrust, thrust, fire, ShipInput::default(), thrust]);
+
+        let loaded = Replay::from_bytes(&original.to_bytes()).unwrap();
+
+        assert_eq!(loaded.seed, original.seed);
+        assert_eq!(loaded.wave_file_hash, original.wave_file_hash);
+        assert_eq!(loaded.tick_rate, original.tick_rate);
+        assert_eq!(loaded.mode, original.mode);
+        assert_eq!(loaded.inputs, original.inputs);
+    }
+
+    #[test]
+    fn splits_runs_longer_than_a_u16() {
+        let inputs = vec![ShipInput { thrust: true, ..default() }; u16::MAX as usize * 2 + 10];
+        let bytes = replay(inputs.clone()).to_bytes();
+
+        assert_eq!(bytes.len(), HEADER_LEN + 3 * RUN_LEN);
+        assert_eq!(Replay::from_bytes(&bytes).unwrap().inputs, inputs);
+    }
+
+    #[test]
+    fn rejects_truncated_files() {
+        let bytes = replay(vec![ShipInput::default(); 3]).to_bytes();
+
+        assert!(matches!(Replay::from_bytes(&bytes[..HEADER_LEN - 1]), Err(ReplayError::Truncated)));
+        assert!(matches!(Replay::from_bytes(&bytes[..bytes.len() - 1]), Err(ReplayError::Truncated)));
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        let mut bytes = replay(Vec::new()).to_bytes();
+        bytes[0] = b'X';
+
+        assert!(matches!(Replay::from_bytes(&bytes), Err(ReplayError::NotAReplay)));
+    }
+
+    #[test]
+    fn rejects_unknown_versions_and_modes() {
+        let mut bytes = replay(Vec::new()).to_bytes();
+        bytes[MODE_OFFSET] = 7;
+        assert!(matches!(Replay::from_bytes(&bytes), Err(ReplayError::UnknownMode(7))));
+
+        bytes[VERSION_OFFSET] = REPLAY_VERSION + 1;
+        assert!(matches!(Replay::from_bytes(&bytes), Err(ReplayError::UnsupportedVersion(version)) if version == REPLAY_VERSION + 1));
+    }
+}
@@ -0,0 +1,106 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use rand::{rngs::StdRng, SeedableRng};
+
+use super::state::InGame;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Independent random sequences drawn from the same game seed.
+///
+/// Each gameplay stream is read by systems that run in a fixed order, so the same seed and the same
+/// inputs always give the same game. Cosmetic effects draw from their own stream and may use it
+/// from any schedule without disturbing the others.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RngStream {
+    /// Generated endless waves.
+    Waves,
+    /// Where meteors appear and how they move and spin.
+    Meteors,
+    /// Which saucers appear, where, and how they wander.
+    Saucers,
+    /// Spread of the saucers' shots.
+    Aim,
+    /// Hyperspace destinations and self-destruction.
+    Hyperspace,
+    /// Particles, debris and other effects with no influence on the game.
+    Cosmetic,
+}
+
+impl RngStream {
+    const ALL: [RngStream; 6] = [RngStream::Waves, RngStream::Meteors, RngStream::Saucers, RngStream::Aim, RngStream::Hyperspace, RngStream::Cosmetic];
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+// region:    --- Resources
+#[derive(Resource)]
+pub struct GameRng {
+    seed: u64,
+    streams: Vec<StdRng>,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        let streams = RngStream::ALL.iter()
+            .map(|stream| StdRng::seed_from_u64(mix(seed ^ (stream.index() as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15))))
+            .collect();
+
+        Self { seed, streams }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn stream(&mut self, stream: RngStream) -> &mut StdRng {
+        &mut self.streams[stream.index()]
+    }
+}
+
+/// Seed given on the command line, `None` to draw a new one for every game.
+#[derive(Resource, Clone, Copy)]
+pub struct RngSettings {
+    pub seed: Option<u64>,
+}
+// endregion: --- Resources
+
+pub struct RngPlugin {
+    pub seed: Option<u64>,
+}
+
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .insert_resource(RngSettings { seed: self.seed })
+            .insert_resource(GameRng::new(self.seed.unwrap_or_else(rand::random)))
+            .add_systems(OnEnter(InGame), reseed_system);
+    }
+}
+
+/// Restarts every stream at the start of a game, so a given seed always plays the same.
+pub fn reseed_system(mut commands: Commands, settings: Res<RngSettings>) {
+    let seed = settings.seed.unwrap_or_else(rand::random);
+
+    info!("Game seed: {seed}");
+    commands.insert_resource(GameRng::new(seed));
+}
+
+/// Seed shared by everyone playing the daily challenge on the same UTC day.
+pub fn daily_seed() -> u64 {
+    let day = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs() / SECONDS_PER_DAY);
+
+    mix(day)
+}
+
+/// SplitMix64 finaliser: spreads close inputs, like consecutive days, over unrelated seeds.
+fn mix(value: u64) -> u64 {
+    let mut value = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+
+    value ^ (value >> 31)
+}
@@ -0,0 +1,85 @@
+use bevy::prelude::*;
+
+use super::{components::Saucer, player::Lives, state::{field_active, GameState, InGame}};
+
+// region:    --- Constants
+
+const LARGE_METEOR_POINTS: u32 = 20;
+const MEDIUM_METEOR_POINTS: u32 = 50;
+const SMALL_METEOR_POINTS: u32 = 100;
+
+const LARGE_SAUCER_POINTS: u32 = 200;
+const SMALL_SAUCER_POINTS: u32 = 1000;
+// endregion: --- Constants
+
+// region:    --- Resources
+#[derive(Resource)]
+pub struct Score {
+    pub points: u32,
+    next_extra_life: u32,
+}
+
+impl Score {
+    pub fn new(extra_life_settings: &ExtraLifeSettings) -> Self {
+        Self { points: 0, next_extra_life: extra_life_settings.first }
+    }
+
+    pub fn add(&mut self, points: u32) {
+        self.points += points;
+    }
+}
+
+/// Score thresholds granting an extra life: the first one at `first` points, then one every `every` points.
+#[derive(Resource)]
+pub struct ExtraLifeSettings {
+    pub first: u32,
+    pub every: u32,
+}
+
+impl Default for ExtraLifeSettings {
+    fn default() -> Self {
+        Self { first: 10_000, every: 10_000 }
+    }
+}
+// endregion: --- Resources
+
+pub struct ScorePlugin;
+
+impl Plugin for ScorePlugin {
+    fn build(&self, app: &mut App) {
+        let extra_life_settings = ExtraLifeSettings::default();
+
+        app
+            .insert_resource(Score::new(&extra_life_settings))
+            .insert_resource(extra_life_settings)
+            .add_systems(OnEnter(InGame), reset_score_system)
+            .add_systems(FixedUpdate, extra_life_system.run_if(field_active.and_then(resource_changed::<Score>)));
+    }
+}
+
+/// Smaller fragments are harder to hit, so they are worth more.
+pub fn meteor_points(level: u8) -> u32 {
+    match level {
+        0 | 1 => LARGE_METEOR_POINTS,
+        2 => MEDIUM_METEOR_POINTS,
+        _ => SMALL_METEOR_POINTS,
+    }
+}
+
+pub fn saucer_points(saucer: &Saucer) -> u32 {
+    match saucer {
+        Saucer::Large => LARGE_SAUCER_POINTS,
+        Saucer::Small => SMALL_SAUCER_POINTS,
+    }
+}
+
+fn reset_score_system(mut score: ResMut<Score>, extra_life_settings: Res<ExtraLifeSettings>) {
+    *score = Score::new(&extra_life_settings);
+}
+
+fn extra_life_system(mut score: ResMut<Score>, mut lives: ResMut<Lives>, extra_life_settings: Res<ExtraLifeSettings>) {
+    while extra_life_settings.every > 0 && score.points >= score.next_extra_life {
+        lives.0 += 1;
+        score.next_extra_life += extra_life_settings.every;
+    }
+}
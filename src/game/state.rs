@@ -0,0 +1,101 @@
+use bevy::{prelude::*, state::state::StateTransition};
+use bevy_rapier2d::plugin::RapierConfiguration;
+
+// region:    --- Constants
+
+const PAUSE_FONT_SIZE: f32 = 40.;
+// endregion: --- Constants
+
+// region:    --- States
+#[derive(States, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum GameState {
+    #[default]
+    MainMenu,
+    Playing,
+    Paused,
+    GameOver,
+    WaveIntermission,
+}
+
+/// Active for as long as a game is running, pauses and intermissions included.
+/// Gameplay entities are scoped to it so that leaving a game clears the field.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct InGame;
+
+impl ComputedStates for InGame {
+    type SourceStates = GameState;
+
+    fn compute(sources: GameState) -> Option<Self> {
+        match sources {
+            GameState::Playing | GameState::Paused | GameState::WaveIntermission => Some(InGame),
+            GameState::MainMenu | GameState::GameOver => None,
+        }
+    }
+}
+// endregion: --- States
+
+/// True while the field is simulated: during a wave and in the intermission that follows it.
+/// Spawning is limited to `GameState::Playing`, everything already on screen keeps moving.
+pub fn field_active(state: Option<Res<State<GameState>>>) -> bool {
+    state.is_some_and(|state| matches!(state.get(), GameState::Playing | GameState::WaveIntermission))
+}
+
+pub struct StatePlugin;
+
+impl Plugin for StatePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_state::<GameState>()
+            .add_computed_state::<InGame>()
+            .enable_state_scoped_entities::<GameState>()
+            .enable_state_scoped_entities::<InGame>()
+            .add_systems(OnEnter(GameState::Paused), (pause_physics_system, pause_screen_spawn_system))
+            .add_systems(OnExit(GameState::Paused), resume_physics_system)
+            .add_systems(FixedLast, fixed_state_transition_system)
+            .add_systems(Update, toggle_pause_system.run_if(in_state(GameState::Playing).or_else(in_state(GameState::Paused))));
+    }
+}
+
+fn toggle_pause_system(kb: Res<ButtonInput<KeyCode>>, state: Res<State<GameState>>, mut next_state: ResMut<NextState<GameState>>) {
+    if !kb.just_pressed(KeyCode::Escape) && !kb.just_pressed(KeyCode::KeyP) {
+        return;
+    }
+
+    match state.get() {
+        GameState::Playing => next_state.set(GameState::Paused),
+        GameState::Paused => next_state.set(GameState::Playing),
+        _ => {}
+    }
+}
+
+/// Applies the state changes requested during a tick before the next one runs, so that the number of
+/// ticks sharing a frame never changes how a game plays out.
+pub fn fixed_state_transition_system(world: &mut World) {
+    world.run_schedule(StateTransition);
+}
+
+fn pause_physics_system(mut rapier_configuration: ResMut<RapierConfiguration>) {
+    rapier_configuration.physics_pipeline_active = false;
+}
+
+fn resume_physics_system(mut rapier_configuration: ResMut<RapierConfiguration>) {
+    rapier_configuration.physics_pipeline_active = true;
+}
+
+fn pause_screen_spawn_system(mut commands: Commands) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(StateScoped(GameState::Paused))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section("PAUSED", TextStyle { font_size: PAUSE_FONT_SIZE, color: Color::WHITE, ..default() }));
+        });
+}
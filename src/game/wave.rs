@@ -1,77 +1,248 @@
-use std::sync::atomic::{AtomicUsize, Ordering};
+use bevy::prelude::*;
+use rand::{seq::SliceRandom, Rng};
 
-use bevy::{math::Vec2, prelude::Resource};
-use yaml_rust2::{Yaml, YamlLoader};
+use super::{components::{Enemy, Meteor}, meteor::{FragmentSpawnSet, MeteorDefinition, MeteorSpawn, SpawnEdge, SplitRules}, rng::{GameRng, RngStream}, state::{GameState, InGame}, wave_file::{WaveFile, WaveFileLoader}, DestroyedMeteors, GameMode};
 
-use super::meteor::{self, MeteorDefinition};
-extern crate yaml_rust2;
+const WAVE_DATA: &str = "waves.yml";
+const FIRST_WAVE: usize = 1;
+const INTERMISSION_DURATION: f32 = 3.;
+const INTERMISSION_FONT_SIZE: f32 = 40.;
+const GENERATED_SPAWN_INTERVAL: f32 = 1.5;
 
-static mut WAVE_COUNT: AtomicUsize = AtomicUsize::new(1);
-const WAVE_DATA: &str = "resources/waves.yml";
+// region:    --- Resources
+/// Number of the wave being played, reset at the start of every game.
+#[derive(Resource)]
+pub struct WaveCount(pub usize);
 
+impl Default for WaveCount {
+    fn default() -> Self {
+        Self(FIRST_WAVE)
+    }
+}
+
+#[derive(Resource)]
+struct IntermissionTimer(Timer);
+
+#[derive(Resource)]
+struct WaveFileHandle(Handle<WaveFile>);
 
 #[derive(Debug, Resource)]
 pub struct Wave {
-    meteors: Vec<MeteorDefinition>,
-    enemies: i32
+    number: usize,
+    /// Meteors still to spawn, sorted by spawn time.
+    meteors: Vec<MeteorSpawn>,
+    enemies: i32,
+    elapsed: f32
+}
+// endregion: --- Resources
+
+/// Spawns what the `Wave` queue holds; checking for a cleared wave runs after it.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WaveSpawnSet;
+
+pub struct WavePlugin;
+
+impl Plugin for WavePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<WaveCount>()
+            .init_asset::<WaveFile>()
+            .init_asset_loader::<WaveFileLoader>()
+            .add_systems(Startup, load_wave_file_system)
+            .add_systems(Update, (wave_file_loaded_system, wave_file_failed_system))
+            .add_systems(OnEnter(InGame), init_wave_system)
+            .add_systems(OnEnter(GameState::WaveIntermission), intermission_spawn_system)
+            .add_systems(FixedUpdate, wave_cleared_system.after(FragmentSpawnSet).after(WaveSpawnSet).run_if(in_state(GameState::Playing)))
+            .add_systems(FixedUpdate, next_wave_system.run_if(in_state(GameState::WaveIntermission)));
+    }
 }
 
-impl Wave {
-    pub fn new() -> Self {
-        let yaml = Self::get_yaml_access();
+fn load_wave_file_system(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(WaveFileHandle(asset_server.load(WAVE_DATA)));
+}
 
-        let (meteors_wave_one, enemies_wave_one) = Self::parse_wave_data(yaml, Self::get_wave_count());
-        Self::increment_wave_count();
+/// Copies every successful (re)load of the wave file into the `WaveFile` resource read when a wave starts.
+fn wave_file_loaded_system(
+    mut commands: Commands,
+    mut asset_events: EventReader<AssetEvent<WaveFile>>,
+    wave_file_handle: Res<WaveFileHandle>,
+    wave_files: Res<Assets<WaveFile>>,
+    current_wave_file: Option<Res<WaveFile>>
+) {
+    for event in asset_events.read() {
+        if !event.is_loaded_with_dependencies(&wave_file_handle.0) {
+            continue;
+        }
 
-        Wave { meteors: meteors_wave_one, enemies: enemies_wave_one }
+        let Some(wave_file) = wave_files.get(&wave_file_handle.0) else {
+            continue;
+        };
+
+        if current_wave_file.is_some() {
+            info!("{WAVE_DATA} reloaded, {} waves, used from the next wave on", wave_file.waves.len());
+        }
+        commands.insert_resource(wave_file.clone());
     }
+}
 
-    fn get_yaml_access() -> Yaml {
-        let yaml_file = std::fs::read_to_string(WAVE_DATA).unwrap();
-        let yaml_file_content = YamlLoader::load_from_str(&yaml_file).unwrap();
-        yaml_file_content[0].clone()
+fn wave_file_failed_system(
+    mut failed_events: EventReader<AssetLoadFailedEvent<WaveFile>>,
+    mut app_exit_events: EventWriter<AppExit>,
+    current_wave_file: Option<Res<WaveFile>>
+) {
+    for event in failed_events.read() {
+        error!("{}: {}", event.path, event.error);
+
+        if current_wave_file.is_some() {
+            warn!("Keeping the last valid version of {WAVE_DATA}");
+        } else {
+            app_exit_events.send(AppExit::error());
+        }
     }
+}
 
+fn init_wave_system(mut commands: Commands, wave_file: Res<WaveFile>, mut wave_count: ResMut<WaveCount>) {
+    *wave_count = WaveCount::default();
 
-    fn increment_wave_count() {
-        unsafe {
-            WAVE_COUNT.fetch_add(1, Ordering::SeqCst);
-        }
+    match Wave::new(&wave_file, wave_count.0) {
+        Some(wave) => commands.insert_resource(wave),
+        None => panic!("{WAVE_DATA} does not define wave {}", wave_count.0),
     }
+}
 
-    fn get_wave_count() -> usize {
-        unsafe {
-            WAVE_COUNT.load(Ordering::SeqCst)
-        }
+fn wave_cleared_system(
+    wave: Res<Wave>,
+    destroyed_meteors: Res<DestroyedMeteors>,
+    mut next_state: ResMut<NextState<GameState>>,
+    query_meteor: Query<(), With<Meteor>>,
+    query_enemy: Query<(), With<Enemy>>
+) {
+    let cleared = !wave.has_meteors()
+        && !wave.has_enemies()
+        && destroyed_meteors.0.is_empty()
+        && query_meteor.is_empty()
+        && query_enemy.is_empty();
+
+    if cleared {
+        info!("Wave {} cleared", wave.get_number());
+        next_state.set(GameState::WaveIntermission);
+    }
+}
+
+fn intermission_spawn_system(mut commands: Commands, wave_count: Res<WaveCount>) {
+    commands.insert_resource(IntermissionTimer(Timer::from_seconds(INTERMISSION_DURATION, TimerMode::Once)));
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(StateScoped(GameState::WaveIntermission))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                format!("WAVE {}", wave_count.0 + 1),
+                TextStyle { font_size: INTERMISSION_FONT_SIZE, color: Color::WHITE, ..default() }
+            ));
+        });
+}
+
+fn next_wave_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    game_mode: Res<GameMode>,
+    wave_file: Res<WaveFile>,
+    mut game_rng: ResMut<GameRng>,
+    mut intermission_timer: ResMut<IntermissionTimer>,
+    mut wave_count: ResMut<WaveCount>,
+    mut next_state: ResMut<NextState<GameState>>
+) {
+    if !intermission_timer.0.tick(time.delta()).just_finished() {
+        return;
     }
 
-    fn parse_wave_data(yaml: Yaml, index: usize) -> (Vec<MeteorDefinition>, i32) {
-        let mut meteors_definition = Vec::new();
-        
-        for meteor in yaml[index]["meteors"].clone() {
-            meteors_definition.push(MeteorDefinition {
-                weight: meteor["weight"].as_f64().unwrap() as f32,
-                speed: [
-                    meteor["speed"][0].as_f64().unwrap() as f32,
-                    meteor["speed"][1].as_f64().unwrap() as f32,
-                ],
-                kind: meteor["kind"].as_i64().unwrap() as u8,
-                level: meteor["level"].as_i64().unwrap() as u8,
-            });
+    commands.remove_resource::<IntermissionTimer>();
+    wave_count.0 += 1;
+
+    match Wave::new(&wave_file, wave_count.0) {
+        Some(wave) => {
+            commands.insert_resource(wave);
+            next_state.set(GameState::Playing);
+        },
+        None if *game_mode == GameMode::Endless => {
+            commands.insert_resource(Wave::generate(&wave_file, wave_count.0, game_rng.stream(RngStream::Waves)));
+            next_state.set(GameState::Playing);
+        },
+        None => {
+            info!("Every wave of {WAVE_DATA} has been cleared");
+            next_state.set(GameState::GameOver);
         }
+    }
+}
+
+impl Wave {
+    /// Copies wave `number` out of the wave file, `None` once the file has no such wave.
+    pub fn new(wave_file: &WaveFile, number: usize) -> Option<Self> {
+        let wave = wave_file.get_wave(number)?;
 
-        (meteors_definition, yaml[index]["enemies"].as_i64().unwrap() as i32)
+        Some(Wave { number, meteors: wave.meteors.clone(), enemies: wave.enemies, elapsed: 0. })
+    }
+
+    /// Builds wave `number` from the endless curve, for waves past the last one of the wave file.
+    pub fn generate(wave_file: &WaveFile, number: usize, rng: &mut impl Rng) -> Self {
+        let curve = &wave_file.endless;
+        let waves_past_file = number.saturating_sub(wave_file.get_last_wave_number()) as f32;
+
+        let meteors_count = ((curve.meteors_base + curve.meteors_per_wave * waves_past_file) as usize).min(curve.meteors_max);
+        let speed_increase = curve.speed_per_wave * waves_past_file;
+        let speed_min = (curve.speed[0] + speed_increase).min(curve.speed_max);
+        let speed_max = (curve.speed[1] + speed_increase).min(curve.speed_max).max(speed_min);
+
+        let meteors = (0..meteors_count)
+            .map(|index| MeteorSpawn {
+                at: index as f32 * GENERATED_SPAWN_INTERVAL,
+                edge: SpawnEdge::Edges,
+                meteor: MeteorDefinition {
+                    weight: rng.gen_range(curve.weight[0]..=curve.weight[1]),
+                    speed: [speed_min, speed_max],
+                    kind: *curve.kinds.choose(rng).unwrap(),
+                    level: 1,
+                    split: SplitRules::default(),
+                },
+            })
+            .collect();
+
+        let enemies = ((curve.enemies_base + curve.enemies_per_wave * waves_past_file) as i32).min(curve.enemies_max);
+
+        Wave { number, meteors, enemies, elapsed: 0. }
+    }
+
+    pub fn get_number(&self) -> usize {
+        self.number
     }
 
     pub fn has_meteors(&self) -> bool {
         !self.meteors.is_empty()
     }
 
-    pub fn get_meteors(&mut self) -> &mut Vec<MeteorDefinition> {
-        &mut self.meteors
+    /// Advances the wave clock by `delta` seconds and hands over the meteors due by then.
+    pub fn take_due_meteors(&mut self, delta: f32) -> Vec<MeteorSpawn> {
+        self.elapsed += delta;
+        let due = self.meteors.partition_point(|meteor_spawn| meteor_spawn.at <= self.elapsed);
+
+        self.meteors.drain(..due).collect()
+    }
+
+    pub fn has_enemies(&self) -> bool {
+        self.enemies > 0
     }
 
     pub fn get_enemies(&mut self) -> &mut i32 {
         &mut self.enemies
     }
-}
\ No newline at end of file
+}
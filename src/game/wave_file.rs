@@ -0,0 +1,650 @@
+use std::{collections::BTreeMap, fmt, path::Path};
+
+use bevy::{asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext}, prelude::*};
+use yaml_rust2::{Yaml, YamlLoader};
+
+use super::meteor::{MeteorDefinition, MeteorKind, MeteorSpawn, SpawnEdge, SplitRules};
+
+const ENDLESS_KEY: &str = "endless";
+const MAX_SPLIT_LEVEL: i64 = 8;
+const MAX_SPLIT_CHILDREN: i64 = 8;
+/// Most meteors a single entry may break into by its last split level.
+const MAX_SPLIT_DESCENDANTS: u64 = 256;
+
+// region:    --- Schema
+#[derive(Debug, Clone)]
+pub struct WaveDefinition {
+    pub number: usize,
+    /// Timeline of the wave, sorted by spawn time.
+    pub meteors: Vec<MeteorSpawn>,
+    pub enemies: i32,
+}
+
+/// How generated waves get harder in endless mode, read from the `endless` section of the wave file.
+/// Every value grows linearly with the number of waves played past the file and stops at its cap.
+#[derive(Debug, Clone)]
+pub struct EndlessCurve {
+    pub meteors_base: f32,
+    pub meteors_per_wave: f32,
+    pub meteors_max: usize,
+    pub speed: [f32; 2],
+    pub speed_per_wave: f32,
+    pub speed_max: f32,
+    pub weight: [f32; 2],
+    pub kinds: Vec<MeteorKind>,
+    pub enemies_base: f32,
+    pub enemies_per_wave: f32,
+    pub enemies_max: i32,
+}
+
+impl Default for EndlessCurve {
+    fn default() -> Self {
+        Self {
+            meteors_base: 4.,
+            meteors_per_wave: 0.5,
+            meteors_max: 12,
+            speed: [30., 50.],
+            speed_per_wave: 2.,
+            speed_max: 100.,
+            weight: [10., 30.],
+            kinds: vec![MeteorKind::Rocky],
+            enemies_base: 1.,
+            enemies_per_wave: 0.25,
+            enemies_max: 4,
+        }
+    }
+}
+
+/// Every authored wave, indexed by number, plus the curve used past the last one.
+/// Loaded as an asset, then copied into a resource so the last valid version survives a bad edit.
+#[derive(Asset, TypePath, Debug, Clone, Resource)]
+pub struct WaveFile {
+    pub waves: BTreeMap<usize, WaveDefinition>,
+    pub endless: EndlessCurve,
+    /// FNV-1a hash of the source text, recorded in replays to catch a different wave file.
+    pub hash: u64,
+}
+
+impl WaveFile {
+    pub fn get_wave(&self, number: usize) -> Option<&WaveDefinition> {
+        self.waves.get(&number)
+    }
+
+    pub fn get_last_wave_number(&self) -> usize {
+        self.waves.keys().next_back().copied().unwrap_or(0)
+    }
+}
+// endregion: --- Schema
+
+// region:    --- Errors
+#[derive(Debug, Clone)]
+pub struct WaveError {
+    pub wave: Option<usize>,
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for WaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.wave, self.path.is_empty()) {
+            (Some(wave), true) => write!(f, "wave {wave}: {}", self.message),
+            (Some(wave), false) => write!(f, "wave {wave}, {}: {}", self.path, self.message),
+            (None, true) => write!(f, "{}", self.message),
+            (None, false) => write!(f, "{}: {}", self.path, self.message),
+        }
+    }
+}
+
+impl std::error::Error for WaveError {}
+
+#[derive(Debug)]
+pub enum WaveFileLoaderError {
+    Io(std::io::Error),
+    Utf8(std::string::FromUtf8Error),
+    Invalid(Vec<WaveError>),
+}
+
+impl fmt::Display for WaveFileLoaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WaveFileLoaderError::Io(error) => write!(f, "cannot read wave file: {error}"),
+            WaveFileLoaderError::Utf8(error) => write!(f, "wave file is not valid UTF-8: {error}"),
+            WaveFileLoaderError::Invalid(errors) => {
+                let errors = errors.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ");
+                write!(f, "invalid wave file: {errors}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for WaveFileLoaderError {}
+// endregion: --- Errors
+
+#[derive(Default)]
+pub struct WaveFileLoader;
+
+impl AssetLoader for WaveFileLoader {
+    type Asset = WaveFile;
+    type Settings = ();
+    type Error = WaveFileLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await.map_err(WaveFileLoaderError::Io)?;
+
+        let content = String::from_utf8(bytes).map_err(WaveFileLoaderError::Utf8)?;
+        parse_wave_file(&content).map_err(WaveFileLoaderError::Invalid)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["yml", "yaml"]
+    }
+}
+
+pub fn load_wave_file(path: impl AsRef<Path>) -> Result<WaveFile, Vec<WaveError>> {
+    let path = path.as_ref();
+
+    let content = std::fs::read_to_string(path).map_err(|error| vec![WaveError {
+        wave: None,
+        path: String::new(),
+        message: format!("cannot read {}: {error}", path.display()),
+    }])?;
+
+    parse_wave_file(&content)
+}
+
+/// Parses and checks a whole wave file, reporting every problem found rather than only the first one.
+pub fn parse_wave_file(content: &str) -> Result<WaveFile, Vec<WaveError>> {
+    let mut parser = WaveParser::default();
+
+    let documents = YamlLoader::load_from_str(content).map_err(|error| vec![WaveError {
+        wave: None,
+        path: String::new(),
+        message: format!("invalid YAML: {error}"),
+    }])?;
+
+    let Some(Yaml::Hash(root)) = documents.first() else {
+        parser.error("", "expected a mapping of wave numbers to waves");
+        return Err(parser.errors);
+    };
+
+    let mut waves = BTreeMap::new();
+    let mut wave_numbers = Vec::new();
+    let mut endless = EndlessCurve::default();
+
+    for (key, value) in root {
+        match key {
+            Yaml::Integer(number) if *number >= 1 => {
+                wave_numbers.push(*number as usize);
+                parser.wave = Some(*number as usize);
+                if let Some(wave) = parser.wave_definition(*number as usize, value) {
+                    waves.insert(wave.number, wave);
+                }
+                parser.wave = None;
+            },
+            Yaml::String(name) if name == ENDLESS_KEY => endless = parser.endless_curve(value),
+            _ => parser.error(&describe(key), "wave numbers must be integers starting at 1"),
+        }
+    }
+
+    if wave_numbers.is_empty() {
+        parser.error("", "the file does not define any wave");
+    }
+
+    wave_numbers.sort_unstable();
+    for (expected, number) in (1..).zip(wave_numbers) {
+        if number != expected {
+            parser.error("", &format!("wave {expected} is missing, waves must be numbered without gaps"));
+            break;
+        }
+    }
+
+    if parser.errors.is_empty() {
+        Ok(WaveFile { waves, endless, hash: hash_content(content) })
+    } else {
+        Err(parser.errors)
+    }
+}
+
+fn hash_content(content: &str) -> u64 {
+    content.bytes().fold(0xCBF2_9CE4_8422_2325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x0100_0000_01B3))
+}
+
+/// Entry point of `--validate-waves <file>`: prints a report and returns whether the file is valid.
+pub fn validate_wave_file(path: &str) -> bool {
+    match load_wave_file(path) {
+        Ok(wave_file) => {
+            println!("{path}: {} waves, OK", wave_file.waves.len());
+            true
+        },
+        Err(errors) => {
+            for error in &errors {
+                eprintln!("{path}: {error}");
+            }
+            eprintln!("{path}: {} error(s)", errors.len());
+            false
+        }
+    }
+}
+
+/// Short human-readable form of a YAML value for error messages.
+fn describe(yaml: &Yaml) -> String {
+    match yaml {
+        Yaml::String(value) => format!("\"{value}\""),
+        Yaml::Integer(value) => value.to_string(),
+        Yaml::Real(value) => value.clone(),
+        Yaml::Boolean(value) => value.to_string(),
+        Yaml::Array(_) => "a list".to_string(),
+        Yaml::Hash(_) => "a mapping".to_string(),
+        Yaml::Null => "nothing".to_string(),
+        Yaml::Alias(_) | Yaml::BadValue => "nothing".to_string(),
+    }
+}
+
+#[derive(Default)]
+struct WaveParser {
+    wave: Option<usize>,
+    errors: Vec<WaveError>,
+}
+
+impl WaveParser {
+    fn error(&mut self, path: &str, message: &str) {
+        self.errors.push(WaveError { wave: self.wave, path: path.to_string(), message: message.to_string() });
+    }
+
+    fn check_keys(&mut self, yaml: &Yaml, path: &str, allowed: &[&str]) {
+        let Some(hash) = yaml.as_hash() else {
+            self.error(path, "expected a mapping");
+            return;
+        };
+
+        for key in hash.keys() {
+            match key.as_str() {
+                Some(key) if allowed.contains(&key) => {},
+                _ => self.error(path, &format!("unknown field {}, expected one of {}", describe(key), allowed.join(", "))),
+            }
+        }
+    }
+
+    fn number(&mut self, yaml: &Yaml, path: &str) -> Option<f32> {
+        match yaml {
+            Yaml::Real(_) => yaml.as_f64().map(|value| value as f32),
+            Yaml::Integer(value) => Some(*value as f32),
+            Yaml::BadValue => {
+                self.error(path, "missing number");
+                None
+            },
+            _ => {
+                self.error(path, &format!("expected a number, found {}", describe(yaml)));
+                None
+            }
+        }
+    }
+
+    fn positive_number(&mut self, yaml: &Yaml, path: &str) -> Option<f32> {
+        let value = self.number(yaml, path)?;
+        if value <= 0. {
+            self.error(path, &format!("must be greater than 0, found {value}"));
+            return None;
+        }
+        Some(value)
+    }
+
+    fn integer(&mut self, yaml: &Yaml, path: &str, min: i64) -> Option<i64> {
+        match yaml.as_i64() {
+            Some(value) if value >= min => Some(value),
+            Some(value) => {
+                self.error(path, &format!("must be at least {min}, found {value}"));
+                None
+            },
+            None => {
+                self.error(path, &format!("expected an integer, found {}", describe(yaml)));
+                None
+            }
+        }
+    }
+
+    fn bounded_integer(&mut self, yaml: &Yaml, path: &str, min: i64, max: i64) -> Option<i64> {
+        let value = self.integer(yaml, path, min)?;
+        if value > max {
+            self.error(path, &format!("must be at most {max}, found {value}"));
+            return None;
+        }
+        Some(value)
+    }
+
+    fn range(&mut self, yaml: &Yaml, path: &str) -> Option<[f32; 2]> {
+        match yaml.as_vec().map(Vec::as_slice) {
+            Some([min, max]) => {
+                let min = self.number(min, &format!("{path}[0]"));
+                let max = self.number(max, &format!("{path}[1]"));
+                let (min, max) = (min?, max?);
+
+                if min < 0. {
+                    self.error(path, &format!("min ({min}) must not be negative"));
+                    return None;
+                }
+                if min > max {
+                    self.error(path, &format!("min ({min}) is greater than max ({max})"));
+                    return None;
+                }
+                Some([min, max])
+            },
+            _ => {
+                self.error(path, "expected a [min, max] pair");
+                None
+            }
+        }
+    }
+
+    fn kind(&mut self, yaml: &Yaml, path: &str) -> Option<MeteorKind> {
+        let kind = match yaml {
+            Yaml::Integer(id) => MeteorKind::from_id(*id),
+            Yaml::String(name) => MeteorKind::from_name(name),
+            _ => None,
+        };
+
+        if kind.is_none() {
+            let known = MeteorKind::ALL.iter().map(|kind| format!("{} ({})", kind.id(), kind.name())).collect::<Vec<_>>().join(", ");
+            self.error(path, &format!("unknown kind {}, expected one of {known}", describe(yaml)));
+        }
+        kind
+    }
+
+    fn edge(&mut self, yaml: &Yaml, path: &str) -> Option<SpawnEdge> {
+        let edge = yaml.as_str().and_then(SpawnEdge::from_name);
+
+        if edge.is_none() {
+            let known = SpawnEdge::ALL.iter().map(SpawnEdge::name).collect::<Vec<_>>().join(", ");
+            self.error(path, &format!("unknown edge {}, expected one of {known}", describe(yaml)));
+        }
+        edge
+    }
+
+    fn wave_definition(&mut self, number: usize, yaml: &Yaml) -> Option<WaveDefinition> {
+        self.check_keys(yaml, "", &["meteors", "enemies"]);
+
+        let enemies = match &yaml["enemies"] {
+            Yaml::BadValue => Some(0),
+            enemies => self.integer(enemies, "enemies", 0),
+        };
+
+        let Some(entries) = yaml["meteors"].as_vec() else {
+            self.error("meteors", "expected a list of meteors");
+            return None;
+        };
+
+        let errors_before = self.errors.len();
+        let mut previous_at = 0.;
+        let mut meteors: Vec<MeteorSpawn> = entries.iter()
+            .enumerate()
+            .filter_map(|(index, meteor)| {
+                let meteor_spawn = self.meteor_spawn(meteor, &format!("meteors[{index}]"), previous_at)?;
+                previous_at = meteor_spawn.at;
+                Some(meteor_spawn)
+            })
+            .collect();
+
+        if self.errors.len() > errors_before {
+            return None;
+        }
+
+        meteors.sort_by(|a, b| a.at.total_cmp(&b.at));
+
+        Some(WaveDefinition { number, meteors, enemies: enemies? as i32 })
+    }
+
+    /// `at` is the spawn time since the start of the wave, `delay` the time since the previous entry.
+    /// Without either, an entry spawns together with the previous one.
+    fn meteor_spawn(&mut self, yaml: &Yaml, path: &str, previous_at: f32) -> Option<MeteorSpawn> {
+        self.check_keys(yaml, path, &["weight", "speed", "kind", "level", "at", "delay", "edge", "max_level", "children", "scale_per_level", "speed_per_level"]);
+
+        let at = match (&yaml["at"], &yaml["delay"]) {
+            (Yaml::BadValue, Yaml::BadValue) => Some(previous_at),
+            (at, Yaml::BadValue) => self.time(at, &format!("{path}.at")),
+            (Yaml::BadValue, delay) => self.time(delay, &format!("{path}.delay")).map(|delay| previous_at + delay),
+            _ => {
+                self.error(path, "use either at or delay, not both");
+                None
+            }
+        };
+        let edge = match &yaml["edge"] {
+            Yaml::BadValue => Some(SpawnEdge::Random),
+            edge => self.edge(edge, &format!("{path}.edge")),
+        };
+        let meteor = self.meteor_definition(yaml, path);
+
+        Some(MeteorSpawn { at: at?, edge: edge?, meteor: meteor? })
+    }
+
+    fn time(&mut self, yaml: &Yaml, path: &str) -> Option<f32> {
+        let value = self.number(yaml, path)?;
+        if value < 0. {
+            self.error(path, &format!("must not be negative, found {value}"));
+            return None;
+        }
+        Some(value)
+    }
+
+    fn meteor_definition(&mut self, yaml: &Yaml, path: &str) -> Option<MeteorDefinition> {
+        let weight = self.positive_number(&yaml["weight"], &format!("{path}.weight"));
+        let speed = self.range(&yaml["speed"], &format!("{path}.speed"));
+        let kind = self.kind(&yaml["kind"], &format!("{path}.kind"));
+        let level = self.bounded_integer(&yaml["level"], &format!("{path}.level"), 1, u8::MAX as i64)
+            .map(|level| level as u8);
+        let split = self.split_rules(yaml, path, kind, level);
+
+        Some(MeteorDefinition { weight: weight?, speed: speed?, kind: kind?, level: level?, split: split? })
+    }
+
+    /// Optional split fields of a meteor entry, falling back to `SplitRules::default` one by one.
+    fn split_rules(&mut self, yaml: &Yaml, path: &str, kind: Option<MeteorKind>, level: Option<u8>) -> Option<SplitRules> {
+        let mut split = SplitRules::default();
+        let errors_before = self.errors.len();
+
+        let level = level.unwrap_or(1);
+        if yaml["max_level"].is_badvalue() {
+            split.max_level = split.max_level.max(level);
+        } else {
+            let path = format!("{path}.max_level");
+            match self.integer(&yaml["max_level"], &path, level as i64) {
+                Some(max_level) if max_level > MAX_SPLIT_LEVEL => self.error(&path, &format!("must be at most {MAX_SPLIT_LEVEL}, found {max_level}")),
+                Some(max_level) => split.max_level = max_level as u8,
+                None => {},
+            }
+        }
+        if !yaml["children"].is_badvalue() {
+            if let Some(children) = self.bounded_integer(&yaml["children"], &format!("{path}.children"), 0, MAX_SPLIT_CHILDREN) {
+                split.children = Some(children as usize);
+            }
+        }
+        if !yaml["scale_per_level"].is_badvalue() {
+            let path = format!("{path}.scale_per_level");
+            match self.positive_number(&yaml["scale_per_level"], &path) {
+                Some(scale) if scale > 1. => self.error(&path, &format!("fragments cannot be bigger than their parent, found {scale}")),
+                Some(scale) => split.scale_per_level = Some(scale),
+                None => {},
+            }
+        }
+        if !yaml["speed_per_level"].is_badvalue() {
+            if let Some(speed) = self.positive_number(&yaml["speed_per_level"], &format!("{path}.speed_per_level")) {
+                split.speed_per_level = speed;
+            }
+        }
+        if let Some(kind) = kind {
+            let children = split.fragments(kind);
+            let depth = split.max_level.saturating_sub(level) as u32;
+            let descendants = (children as u64).saturating_pow(depth);
+            if descendants > MAX_SPLIT_DESCENDANTS {
+                let field = if yaml["children"].is_badvalue() { "max_level" } else { "children" };
+                self.error(&format!("{path}.{field}"), &format!("{children} fragments over {depth} levels make {descendants} meteors, at most {MAX_SPLIT_DESCENDANTS} allowed"));
+            }
+        }
+
+        (self.errors.len() == errors_before).then_some(split)
+    }
+
+    fn endless_curve(&mut self, yaml: &Yaml) -> EndlessCurve {
+        let mut curve = EndlessCurve::default();
+        self.check_keys(yaml, ENDLESS_KEY, &["meteors", "speed", "weight", "kinds", "enemies"]);
+
+        let optional_number = |parser: &mut Self, value: &Yaml, path: &str, target: &mut f32| {
+            if !value.is_badvalue() {
+                if let Some(value) = parser.number(value, path) {
+                    *target = value;
+                }
+            }
+        };
+
+        optional_number(self, &yaml["meteors"]["base"], "endless.meteors.base", &mut curve.meteors_base);
+        optional_number(self, &yaml["meteors"]["per_wave"], "endless.meteors.per_wave", &mut curve.meteors_per_wave);
+        optional_number(self, &yaml["speed"]["per_wave"], "endless.speed.per_wave", &mut curve.speed_per_wave);
+        optional_number(self, &yaml["speed"]["max"], "endless.speed.max", &mut curve.speed_max);
+        optional_number(self, &yaml["enemies"]["base"], "endless.enemies.base", &mut curve.enemies_base);
+        optional_number(self, &yaml["enemies"]["per_wave"], "endless.enemies.per_wave", &mut curve.enemies_per_wave);
+
+        if !yaml["meteors"]["max"].is_badvalue() {
+            if let Some(max) = self.integer(&yaml["meteors"]["max"], "endless.meteors.max", 1) {
+                curve.meteors_max = max as usize;
+            }
+        }
+        if !yaml["enemies"]["max"].is_badvalue() {
+            if let Some(max) = self.integer(&yaml["enemies"]["max"], "endless.enemies.max", 0) {
+                curve.enemies_max = max as i32;
+            }
+        }
+        if !yaml["speed"]["range"].is_badvalue() {
+            if let Some(speed) = self.range(&yaml["speed"]["range"], "endless.speed.range") {
+                curve.speed = speed;
+            }
+        }
+        if !yaml["weight"].is_badvalue() {
+            if let Some(weight) = self.range(&yaml["weight"], "endless.weight") {
+                if weight[0] > 0. {
+                    curve.weight = weight;
+                } else {
+                    self.error("endless.weight", "weights must be greater than 0");
+                }
+            }
+        }
+        if curve.speed[1] > curve.speed_max {
+            self.error("endless.speed", &format!("range max ({}) is above the speed cap ({})", curve.speed[1], curve.speed_max));
+        }
+
+        match &yaml["kinds"] {
+            Yaml::BadValue => {},
+            Yaml::Array(kinds) if !kinds.is_empty() => {
+                curve.kinds = kinds.iter()
+                    .enumerate()
+                    .filter_map(|(index, kind)| self.kind(kind, &format!("endless.kinds[{index}]")))
+                    .collect();
+            },
+            _ => self.error("endless.kinds", "expected a non-empty list of kinds"),
+        }
+
+        curve
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errors(content: &str) -> Vec<String> {
+        parse_wave_file(content).unwrap_err().iter().map(ToString::to_string).collect()
+    }
+
+    fn wave(meteor: &str) -> String {
+        format!("1:\n  meteors:\n  - {meteor}\n")
+    }
+
+    #[test]
+    fn parses_a_valid_wave() {
+        let wave_file = parse_wave_file(&wave("{ weight: 10., speed: [30., 40.], kind: 1, level: 1 }")).unwrap();
+        let spawn = &wave_file.get_wave(1).unwrap().meteors[0];
+
+        assert_eq!(spawn.meteor.speed, [30., 40.]);
+        assert_eq!(spawn.meteor.kind, MeteorKind::Rocky);
+        assert_eq!(spawn.edge, SpawnEdge::Random);
+        assert_eq!(wave_file.get_last_wave_number(), 1);
+    }
+
+    #[test]
+    fn rejects_a_speed_range_with_min_above_max() {
+        let errors = errors(&wave("{ weight: 10., speed: [50., 40.], kind: 1, level: 1 }"));
+        assert_eq!(errors, ["wave 1, meteors[0].speed: min (50) is greater than max (40)"]);
+    }
+
+    #[test]
+    fn rejects_a_level_below_one() {
+        let errors = errors(&wave("{ weight: 10., speed: [30., 40.], kind: 1, level: 0 }"));
+        assert_eq!(errors, ["wave 1, meteors[0].level: must be at least 1, found 0"]);
+    }
+
+    #[test]
+    fn rejects_an_unknown_kind() {
+        let errors = errors(&wave("{ weight: 10., speed: [30., 40.], kind: 9, level: 1 }"));
+        assert_eq!(errors, ["wave 1, meteors[0].kind: unknown kind 9, expected one of 1 (rocky), 2 (metallic), 3 (icy), 4 (explosive), 5 (magnetic)"]);
+    }
+
+    #[test]
+    fn accepts_kinds_by_name() {
+        let wave_file = parse_wave_file(&wave("{ weight: 10., speed: [30., 40.], kind: metallic, level: 1 }")).unwrap();
+        assert_eq!(wave_file.get_wave(1).unwrap().meteors[0].meteor.kind, MeteorKind::Metallic);
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        let errors = errors(&wave("{ weight: 10., speed: [30., 40.], kind: 1, level: 1, colour: red }"));
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("wave 1, meteors[0]: unknown field \"colour\""), "{}", errors[0]);
+    }
+
+    #[test]
+    fn reports_the_wave_number_of_each_error() {
+        let content = "\
+1:
+  meteors:
+  - { weight: 10., speed: [30., 40.], kind: 1, level: 1 }
+2:
+  meteors:
+  - { weight: 10., speed: [30., 40.], kind: 1, level: 1 }
+  - { weight: -1., speed: [30., 40.], kind: 1, level: 1 }
+";
+        assert_eq!(errors(content), ["wave 2, meteors[1].weight: must be greater than 0, found -1"]);
+    }
+
+    #[test]
+    fn keeps_the_classic_shrink_unless_a_scale_is_given() {
+        let wave_file = parse_wave_file(&wave("{ weight: 10., speed: [30., 40.], kind: 1, level: 1 }")).unwrap();
+        assert_eq!(wave_file.get_wave(1).unwrap().meteors[0].meteor.split.scale_per_level, None);
+
+        let wave_file = parse_wave_file(&wave("{ weight: 10., speed: [30., 40.], kind: 1, level: 1, scale_per_level: 0.7 }")).unwrap();
+        assert_eq!(wave_file.get_wave(1).unwrap().meteors[0].meteor.split.scale_per_level, Some(0.7));
+    }
+
+    #[test]
+    fn caps_the_meteors_a_split_can_produce() {
+        assert_eq!(
+            errors(&wave("{ weight: 10., speed: [30., 40.], kind: 1, level: 1, max_level: 8, children: 3 }")),
+            ["wave 1, meteors[0].children: 3 fragments over 7 levels make 2187 meteors, at most 256 allowed"]
+        );
+        assert_eq!(
+            errors(&wave("{ weight: 10., speed: [30., 40.], kind: icy, level: 1, max_level: 8 }")),
+            ["wave 1, meteors[0].max_level: 3 fragments over 7 levels make 2187 meteors, at most 256 allowed"]
+        );
+
+        assert!(parse_wave_file(&wave("{ weight: 10., speed: [30., 40.], kind: 1, level: 1, max_level: 8, children: 2 }")).is_ok());
+    }
+
+    #[test]
+    fn reports_every_error_at_once() {
+        let errors = errors(&wave("{ weight: 10., speed: [50., 40.], kind: 0, level: 0 }"));
+        assert_eq!(errors.len(), 3);
+    }
+}
@@ -0,0 +1,154 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::{ActiveCollisionTypes, ActiveEvents, Collider, RigidBody, Velocity};
+use rand::Rng;
+
+use super::{state::{field_active, GameState, InGame}, collision::CollisionLayer, rng::{GameRng, RngStream}, components::{Damage, Enemy, FromEnemy, FromPlayer, Laser, LifeTime, Player, ScreenWrap, Weapon}, GameTextures, LASER_SIZE, SPRITE_SCALE};
+
+// region:    --- Constants
+
+pub const LASER_SPEED: f32 = 500.;
+const LASER_LIFE_TIME: f32 = 1.;
+// endregion: --- Constants
+
+// region:    --- Resources
+#[derive(Resource)]
+pub struct Difficulty {
+    /// 0 makes enemies fire in random directions, 1 makes them lead the player perfectly.
+    pub enemy_accuracy: f32,
+}
+
+impl Default for Difficulty {
+    fn default() -> Self {
+        Self { enemy_accuracy: 0.5 }
+    }
+}
+// endregion: --- Resources
+
+pub enum LaserSource {
+    Player,
+    Enemy,
+}
+
+pub struct WeaponPlugin;
+
+impl Plugin for WeaponPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<Difficulty>()
+            .add_systems(FixedUpdate, enemy_weapon_system.run_if(field_active));
+    }
+}
+
+pub fn spawn_laser(
+    commands: &mut Commands,
+    game_textures: &Res<GameTextures>,
+    translation: Vec3,
+    angle_radians: f32,
+    linvel: Vec2,
+    damage: f32,
+    source: LaserSource
+) -> Entity {
+    let mut laser = commands.spawn(SpriteBundle {
+        texture: game_textures.laser.clone(),
+        sprite: Sprite {
+            ..Default::default()
+        },
+        transform: Transform {
+            translation,
+            scale: Vec3::new(SPRITE_SCALE, SPRITE_SCALE, 1.),
+            rotation: Quat::from_rotation_z(angle_radians)
+        },
+        ..Default::default()
+    });
+
+    laser
+        .insert(Laser)
+        .insert(ScreenWrap)
+        .insert(StateScoped(InGame))
+        .insert(RigidBody::KinematicVelocityBased)
+        .insert(Collider::capsule(Vec2 { x: 0., y: 0. }, Vec2 { x: 0., y: LASER_SIZE.1 / 2. }, LASER_SIZE.0 / 2.))
+        .insert(ActiveEvents::COLLISION_EVENTS)
+        .insert(Velocity::linear(linvel))
+        .insert(Damage(damage))
+        .insert(LifeTime(Timer::from_seconds(LASER_LIFE_TIME, TimerMode::Once)));
+
+    match source {
+        LaserSource::Player => {
+            laser
+                .insert(FromPlayer)
+                .insert(CollisionLayer::PlayerLaser.collision_groups());
+        },
+        LaserSource::Enemy => {
+            // Enemy shots ignore every saucer, so they can never hit the ship that fired them.
+            laser
+                .insert(FromEnemy)
+                .insert(CollisionLayer::EnemyLaser.collision_groups())
+                .insert(ActiveCollisionTypes::default() | ActiveCollisionTypes::KINEMATIC_STATIC);
+        }
+    }
+
+    laser.id()
+}
+
+fn enemy_weapon_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    game_textures: Res<GameTextures>,
+    mut game_rng: ResMut<GameRng>,
+    mut query_enemy: Query<(&Transform, &mut Weapon), With<Enemy>>,
+    query_player: Query<(&Transform, &Velocity), With<Player>>
+) {
+    let Ok((player_transform, player_velocity)) = query_player.get_single() else {
+        return;
+    };
+
+    for (transform, mut weapon) in query_enemy.iter_mut() {
+        if !weapon.cooldown.tick(time.delta()).just_finished() {
+            continue;
+        }
+
+        let origin = transform.translation.truncate();
+        let aim = get_aim_direction(game_rng.stream(RngStream::Aim), origin, player_transform.translation.truncate(), player_velocity.linvel, weapon.accuracy);
+        let angle_radians = aim.y.atan2(aim.x) - PI / 2.;
+
+        spawn_laser(&mut commands, &game_textures, origin.extend(0.), angle_radians, aim * LASER_SPEED, weapon.damage, LaserSource::Enemy);
+    }
+}
+
+/// Blends the intercept direction towards a moving target with a random spread that shrinks as `accuracy` grows.
+fn get_aim_direction(rng: &mut impl Rng, origin: Vec2, target: Vec2, target_velocity: Vec2, accuracy: f32) -> Vec2 {
+    let intercept = get_intercept_point(origin, target, target_velocity, LASER_SPEED).unwrap_or(target);
+    let lead_angle = (intercept - origin).to_angle();
+
+    let spread = PI * (1. - accuracy.clamp(0., 1.));
+    let angle = lead_angle + if spread > 0. { rng.gen_range(-spread..=spread) } else { 0. };
+
+    Vec2::from_angle(angle)
+}
+
+fn get_intercept_point(origin: Vec2, target: Vec2, target_velocity: Vec2, projectile_speed: f32) -> Option<Vec2> {
+    let to_target = target - origin;
+    let a = target_velocity.length_squared() - projectile_speed * projectile_speed;
+    let b = 2. * to_target.dot(target_velocity);
+    let c = to_target.length_squared();
+
+    let time = if a.abs() < f32::EPSILON {
+        if b.abs() < f32::EPSILON { return None; }
+        -c / b
+    } else {
+        let discriminant = b * b - 4. * a * c;
+        if discriminant < 0. { return None; }
+        let root = discriminant.sqrt();
+        let (t1, t2) = ((-b - root) / (2. * a), (-b + root) / (2. * a));
+        match (t1 > 0., t2 > 0.) {
+            (true, true) => t1.min(t2),
+            (true, false) => t1,
+            (false, true) => t2,
+            (false, false) => return None,
+        }
+    };
+
+    (time > 0.).then(|| target + target_velocity * time)
+}
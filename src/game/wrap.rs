@@ -0,0 +1,179 @@
+use std::collections::BTreeMap;
+
+use bevy::{prelude::*, sprite::{MaterialMesh2dBundle, Mesh2dHandle}};
+use bevy_rapier2d::prelude::{ActiveCollisionTypes, ActiveEvents, Collider, CollisionGroups, Sensor};
+
+use super::{state::{field_active, GameState, InGame}, components::{ClampToScreen, DespawnOffscreen, Ghost, ScreenWrap}, WinSize};
+
+pub struct ScreenWrapPlugin;
+
+impl Plugin for ScreenWrapPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedUpdate, (
+            (correction_screen_overflow_system, ghost_sync_system).chain(),
+            despawn_offscreen_system,
+            clamp_to_screen_system,
+        ).run_if(field_active));
+    }
+}
+
+/// Returns the entity a ghost stands for, or `entity` itself when it is not a ghost.
+pub fn resolve_ghost(entity: Entity, query_ghost: &Query<&Ghost>) -> Entity {
+    query_ghost.get(entity).map_or(entity, |ghost| ghost.owner)
+}
+
+fn correction_screen_overflow_system(win_size: Res<WinSize>, mut query: Query<&mut Transform, With<ScreenWrap>>) {
+    for mut transform in query.iter_mut() {
+        let translation = &mut transform.translation;
+
+        let new_position = |p: f32, screen_size: f32| -> f32 {
+            if p > screen_size / 2. {
+                p - screen_size
+            } else if p < -screen_size / 2. {
+                p + screen_size
+            } else {
+                p
+            }
+        };
+
+        translation.x = new_position(translation.x, win_size.width);
+        translation.y = new_position(translation.y, win_size.height);
+    }
+}
+
+fn despawn_offscreen_system(mut commands: Commands, win_size: Res<WinSize>, query: Query<(Entity, &Transform, &DespawnOffscreen)>) {
+    for (entity, transform, despawn_offscreen) in query.iter() {
+        let translation = transform.translation;
+
+        if translation.x.abs() > win_size.width / 2. + despawn_offscreen.margin
+            || translation.y.abs() > win_size.height / 2. + despawn_offscreen.margin {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn clamp_to_screen_system(win_size: Res<WinSize>, mut query: Query<&mut Transform, With<ClampToScreen>>) {
+    for mut transform in query.iter_mut() {
+        let translation = &mut transform.translation;
+
+        translation.x = translation.x.clamp(-win_size.width / 2., win_size.width / 2.);
+        translation.y = translation.y.clamp(-win_size.height / 2., win_size.height / 2.);
+    }
+}
+
+/// Keeps one ghost per screen edge (or corner) an entity overlaps, drawn on the opposite side.
+fn ghost_sync_system(
+    mut commands: Commands,
+    win_size: Res<WinSize>,
+    query_owner: Query<(
+        Entity,
+        &Transform,
+        &Collider,
+        Option<&Handle<Image>>,
+        Option<&Sprite>,
+        Option<&Mesh2dHandle>,
+        Option<&Handle<ColorMaterial>>,
+        Option<&CollisionGroups>,
+        Option<&Visibility>
+    ), (With<ScreenWrap>, Without<Ghost>)>,
+    mut query_ghost: Query<(Entity, &Ghost, &mut Transform, Option<&mut Sprite>, Option<&mut Visibility>)>
+) {
+    // Ordered by owner, so that leftover ghosts are despawned in the same order on every run.
+    let mut ghosts_by_owner: BTreeMap<Entity, Vec<Entity>> = BTreeMap::new();
+    for (entity_ghost, ghost, ..) in query_ghost.iter() {
+        ghosts_by_owner.entry(ghost.owner).or_default().push(entity_ghost);
+    }
+
+    for (entity, transform, collider, texture, sprite, mesh, material, collision_groups, visibility) in query_owner.iter() {
+        let mut existing_ghosts = ghosts_by_owner.remove(&entity).unwrap_or_default();
+
+        for cell in get_overlapped_cells(&win_size, transform, collider) {
+            let translation = transform.translation + (cell.as_vec2() * Vec2::new(win_size.width, win_size.height)).extend(0.);
+            let ghost_transform = Transform { translation, ..*transform };
+
+            let existing_ghost = existing_ghosts.iter()
+                .position(|entity_ghost| query_ghost.get(*entity_ghost).is_ok_and(|(_, ghost, ..)| ghost.cell == cell));
+
+            if let Some(index) = existing_ghost {
+                let entity_ghost = existing_ghosts.swap_remove(index);
+                if let Ok((_, _, mut transform, ghost_sprite, ghost_visibility)) = query_ghost.get_mut(entity_ghost) {
+                    *transform = ghost_transform;
+                    if let (Some(mut ghost_sprite), Some(sprite)) = (ghost_sprite, sprite) {
+                        *ghost_sprite = sprite.clone();
+                    }
+                    if let (Some(mut ghost_visibility), Some(visibility)) = (ghost_visibility, visibility) {
+                        *ghost_visibility = *visibility;
+                    }
+                }
+                continue;
+            }
+
+            let mut entity_ghost = if let (Some(texture), Some(sprite)) = (texture, sprite) {
+                commands.spawn(SpriteBundle {
+                    texture: texture.clone(),
+                    sprite: sprite.clone(),
+                    transform: ghost_transform,
+                    ..Default::default()
+                })
+            } else if let (Some(mesh), Some(material)) = (mesh, material) {
+                commands.spawn(MaterialMesh2dBundle {
+                    mesh: mesh.clone(),
+                    material: material.clone(),
+                    transform: ghost_transform,
+                    ..Default::default()
+                })
+            } else {
+                continue;
+            };
+
+            // The ghost collider only reports contacts, the owner keeps doing the physics.
+            entity_ghost
+                .insert(Ghost { owner: entity, cell })
+                .insert(StateScoped(InGame))
+                .insert(collider.clone())
+                .insert(Sensor)
+                .insert(ActiveEvents::COLLISION_EVENTS)
+                .insert(ActiveCollisionTypes::default() | ActiveCollisionTypes::KINEMATIC_STATIC);
+
+            if let Some(collision_groups) = collision_groups {
+                entity_ghost.insert(*collision_groups);
+            }
+        }
+
+        for entity_ghost in existing_ghosts {
+            commands.entity(entity_ghost).despawn();
+        }
+    }
+
+    // Whatever is left belongs to owners that were despawned or stopped wrapping.
+    for entity_ghost in ghosts_by_owner.into_values().flatten() {
+        commands.entity(entity_ghost).despawn();
+    }
+}
+
+fn get_overlapped_cells(win_size: &WinSize, transform: &Transform, collider: &Collider) -> Vec<IVec2> {
+    let radius = collider.raw.compute_local_aabb().half_extents().norm() * transform.scale.x.max(transform.scale.y);
+    let position = transform.translation.truncate();
+
+    let get_shifts = |p: f32, screen_size: f32| -> Vec<i32> {
+        let mut shifts = vec![0];
+        if p + radius > screen_size / 2. {
+            shifts.push(-1);
+        }
+        if p - radius < -screen_size / 2. {
+            shifts.push(1);
+        }
+        shifts
+    };
+
+    let mut cells = Vec::new();
+    for x in get_shifts(position.x, win_size.width) {
+        for y in get_shifts(position.y, win_size.height) {
+            if x != 0 || y != 0 {
+                cells.push(IVec2::new(x, y));
+            }
+        }
+    }
+
+    cells
+}
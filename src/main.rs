@@ -9,6 +9,72 @@ use std::collections::HashSet;
 use bevy::{core::FrameCount, diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin}, input::gamepad::{self, ButtonSettingsError}, math::Vec3Swizzles, prelude::*, window::{self, PresentMode, PrimaryWindow, WindowTheme}};
 
 fn main() {
+	let args: Vec<String> = std::env::args().collect();
+	if let Some(index) = args.iter().position(|arg| arg == "--validate-waves") {
+		let Some(path) = args.get(index + 1) else {
+			eprintln!("usage: asteroid --validate-waves <file>");
+			std::process::exit(2);
+		};
+		std::process::exit(if game::validate_wave_file(path) { 0 } else { 1 });
+	}
+
+	let replay = match args.iter().position(|arg| arg == "--replay") {
+		Some(index) => {
+			let Some(path) = args.get(index + 1) else {
+				eprintln!("usage: asteroid --replay <file>");
+				std::process::exit(2);
+			};
+			match game::Replay::load(path) {
+				Ok(replay) => Some(replay),
+				Err(error) => {
+					eprintln!("{path}: {error}");
+					std::process::exit(1);
+				}
+			}
+		},
+		None => None,
+	};
+
+	let record = match args.iter().position(|arg| arg == "--record") {
+		Some(index) => match args.get(index + 1) {
+			Some(path) => Some(std::path::PathBuf::from(path)),
+			None => {
+				eprintln!("usage: asteroid --record <file>");
+				std::process::exit(2);
+			}
+		},
+		None => None,
+	};
+
+	let tick_rate = match args.iter().position(|arg| arg == "--tick-rate") {
+		Some(index) => match args.get(index + 1).and_then(|rate| rate.parse::<f64>().ok()).filter(|rate| *rate > 0.) {
+			Some(rate) => rate,
+			None => {
+				eprintln!("usage: asteroid --tick-rate <ticks per second>");
+				std::process::exit(2);
+			}
+		},
+		None => game::DEFAULT_TICK_RATE,
+	};
+	// A replay only plays out the same at the tick rate it was recorded at.
+	let tick_rate = replay.as_ref().map_or(tick_rate, |replay| replay.tick_rate);
+
+	let seed = if args.iter().any(|arg| arg == "--daily") {
+		Some(game::daily_seed())
+	} else {
+		match args.iter().position(|arg| arg == "--seed") {
+			Some(index) => match args.get(index + 1).and_then(|seed| seed.parse::<u64>().ok()) {
+				Some(seed) => Some(seed),
+				None => {
+					eprintln!("usage: asteroid --seed <number>");
+					std::process::exit(2);
+				}
+			},
+			None => None,
+		}
+	};
+	let seed = replay.as_ref().map(|replay| replay.seed).or(seed);
+
     App::new()
 		.add_plugins((
 			DefaultPlugins.set(WindowPlugin {
@@ -37,10 +103,11 @@ fn main() {
 			// FrameTimeDiagnosticsPlugin,
 		))
 		.add_plugins(RapierDebugRenderPlugin::default())
-		.add_plugins(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0))
+		// Physics steps once per tick, after every gameplay system of `FixedUpdate` has run
+		.add_plugins(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0).in_schedule(FixedPostUpdate))
 		// .add_plugins(InspectableRapierPlugin)
 		.add_plugins(WorldInspectorPlugin::default())
-		.add_plugins(GamePlugin)
+		.add_plugins(GamePlugin { tick_rate, seed, record, replay })
 		.run();
 }
 